@@ -47,6 +47,7 @@ self.onmessage = async (event: MessageEvent<WorkerMessage>) => {
         BigInt(startDate.getTime()),
         BigInt(endDate.getTime()),
         decayHalfLife,
+        undefined,
       );
 
       if (typeof loadResult === "string" && loadResult.startsWith("Error:")) {
@@ -95,6 +95,8 @@ export interface wcif {
 export interface SimulationResult {
   name: string;
   sample_size: number;
+  num_trimmed: number;
+  overridden: boolean;
   win_count: number;
   pod_count: number;
   total_rank: number;
@@ -102,6 +104,53 @@ export interface SimulationResult {
   rank_dist: number[];
   hist_values_single: Map<number, number>;
   hist_values_average: Map<number, number>;
+  // Singles including entered attempts, and singles of only the attempts still to be done
+  hist_single: Histogram;
+  hist_remaining: Histogram;
+  hist_average: Histogram;
+  num_simulations: number;
+  win_se: number;
+  pod_se: number;
+  win_ci: [number, number];
+  pod_ci: [number, number];
+  rank_dist_ci: [number, number][];
+  record_probabilities: RecordProbabilities;
+  // Results are in the same units as entered results, with null for DNF
+  single_percentiles: Percentiles<number | null>;
+  average_percentiles: Percentiles<number | null>;
+  rank_percentiles: Percentiles<number>;
+  expected_best_single: number | null;
+  expected_average: number | null;
+}
+
+// Dense histogram, where bins[i] counts results in [start + i * bin_width, start + (i + 1) * bin_width)
+export interface Histogram {
+  start: number;
+  bin_width: number;
+  bins: number[];
+  underflow: number;
+  overflow: number;
+}
+
+export interface Percentiles<T> {
+  p5: T;
+  p25: T;
+  p50: T;
+  p75: T;
+  p95: T;
+}
+
+// Probabilities are null when the record isn't known
+export interface RecordPairProbability {
+  single: number | null;
+  average: number | null;
+}
+
+export interface RecordProbabilities {
+  personal: RecordPairProbability;
+  national: RecordPairProbability;
+  continental: RecordPairProbability;
+  world: RecordPairProbability;
 }
 
 // Payloads for worker messages
@@ -0,0 +1,252 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::calc::calc_spearman_correlation;
+use crate::calibration::{calc_calibration, CalibrationReport};
+use crate::competitor::ModelOptions;
+use crate::data::CompetitionDataManager;
+use crate::event::EventType;
+use crate::simulation::{CompetitionSimulator, RuntimeConfig};
+
+const MILLISECONDS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+// Keeps the log loss finite when the actual winner was given no chance
+const MIN_PROBABILITY: f32 = 1e-4;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BacktestCase {
+    pub competition_id: String,
+    pub event_id: String,
+    // First day of the competition, YYYY-MM-DD
+    pub date: String,
+    // WCA ids of the finalists
+    pub competitors: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct BacktestOptions {
+    pub num_simulations: u32,
+    pub include_dnf: bool,
+    pub halflife: f32,
+    // Length of the loaded time range before the competition
+    pub window_days: i64,
+    pub model_options: ModelOptions,
+    // Number of equal-width probability bins in the calibration report
+    pub calibration_bins: usize,
+}
+
+impl Default for BacktestOptions {
+    fn default() -> Self {
+        Self {
+            num_simulations: 10000,
+            include_dnf: true,
+            halflife: 180.0,
+            window_days: 730,
+            model_options: ModelOptions::default(),
+            calibration_bins: 10,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CompetitorPrediction {
+    pub wca_id: String,
+    pub name: String,
+    pub win_probability: f32,
+    pub podium_probability: f32,
+    pub expected_rank: f32,
+    // None when the competitor didn't compete in the final
+    pub actual_position: Option<u32>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BacktestCaseResult {
+    competition_id: String,
+    event_id: String,
+    // Metrics are None when there isn't enough data to score the case
+    log_loss: Option<f32>,
+    win_brier: Option<f32>,
+    podium_brier: Option<f32>,
+    spearman: Option<f32>,
+    predictions: Vec<CompetitorPrediction>,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BacktestReport {
+    cases: Vec<BacktestCaseResult>,
+    mean_log_loss: Option<f32>,
+    mean_win_brier: Option<f32>,
+    mean_podium_brier: Option<f32>,
+    mean_spearman: Option<f32>,
+    calibration: CalibrationReport,
+    csv: String,
+}
+
+pub async fn run_backtest(cases: &[BacktestCase], options: &BacktestOptions) -> BacktestReport {
+    let mut results = Vec::with_capacity(cases.len());
+
+    for case in cases {
+        let result = match predict_case(case, options).await {
+            Ok(predictions) => score_case(case, predictions),
+            Err(e) => BacktestCaseResult {
+                competition_id: case.competition_id.clone(),
+                event_id: case.event_id.clone(),
+                log_loss: None,
+                win_brier: None,
+                podium_brier: None,
+                spearman: None,
+                predictions: vec![],
+                error: Some(e.to_string()),
+            },
+        };
+
+        results.push(result);
+    }
+
+    generate_report(results, options)
+}
+
+// Loads results from before the competition, and simulates the final between the finalists
+pub async fn predict_case(
+    case: &BacktestCase,
+    options: &BacktestOptions,
+) -> Result<Vec<CompetitorPrediction>, &'static str> {
+    let event = EventType::from_event_id(&case.event_id).ok_or("Invalid event type")?;
+
+    // Only results from competitions that started before this one are loaded
+    let end_date = NaiveDate::parse_from_str(&case.date, "%Y-%m-%d")
+        .map_err(|_| "Invalid competition date")?
+        .and_hms_opt(0, 0, 0)
+        .ok_or("Invalid competition date")?
+        .and_utc()
+        .timestamp_millis();
+    let start_date = end_date - options.window_days * MILLISECONDS_PER_DAY;
+
+    let data_manager = CompetitionDataManager::create(
+        case.competitors.clone(),
+        vec![event],
+        start_date,
+        end_date,
+        options.halflife,
+        options.model_options.clone(),
+    );
+
+    let (mut competitors_by_event, positions) = data_manager
+        .fetch_all_with_final_positions(&case.competition_id)
+        .await?;
+    let competitors = competitors_by_event.remove(&event).unwrap_or_default();
+
+    if competitors.is_empty() {
+        return Err("No competitors loaded");
+    }
+
+    let names: Vec<String> = competitors.iter().map(|c| c.name.clone()).collect();
+
+    let mut simulator = CompetitionSimulator::new(event, competitors);
+    let mut config = RuntimeConfig {
+        num_simulations: options.num_simulations,
+        include_dnf: options.include_dnf,
+        convergence: None,
+    };
+
+    simulator.run_simulations(&mut config);
+
+    Ok(simulator
+        .outcome_probabilities()
+        .into_iter()
+        .zip(case.competitors.iter().zip(names))
+        .zip(positions)
+        .map(
+            |((outcome, (wca_id, name)), actual_position)| CompetitorPrediction {
+                wca_id: wca_id.clone(),
+                name,
+                win_probability: outcome.win,
+                podium_probability: outcome.podium,
+                expected_rank: outcome.expected_rank,
+                actual_position,
+            },
+        )
+        .collect())
+}
+
+fn score_case(case: &BacktestCase, predictions: Vec<CompetitorPrediction>) -> BacktestCaseResult {
+    let has_outcome = predictions.iter().any(|p| p.actual_position.is_some());
+
+    let log_loss = predictions
+        .iter()
+        .find(|p| p.actual_position == Some(1))
+        .map(|winner| -winner.win_probability.max(MIN_PROBABILITY).ln());
+
+    let brier = |probability: fn(&CompetitorPrediction) -> f32, max_position: u32| {
+        let total: f32 = predictions
+            .iter()
+            .map(|p| {
+                let outcome = p.actual_position.is_some_and(|pos| pos <= max_position);
+                (probability(p) - outcome as u8 as f32).powi(2)
+            })
+            .sum();
+
+        total / predictions.len().max(1) as f32
+    };
+
+    let (expected_ranks, actual_positions): (Vec<f32>, Vec<f32>) = predictions
+        .iter()
+        .filter_map(|p| Some((p.expected_rank, p.actual_position? as f32)))
+        .unzip();
+
+    BacktestCaseResult {
+        competition_id: case.competition_id.clone(),
+        event_id: case.event_id.clone(),
+        log_loss,
+        win_brier: has_outcome.then(|| brier(|p| p.win_probability, 1)),
+        podium_brier: has_outcome.then(|| brier(|p| p.podium_probability, 3)),
+        spearman: calc_spearman_correlation(&expected_ranks, &actual_positions),
+        predictions,
+        error: (!has_outcome).then(|| "No final results found".to_string()),
+    }
+}
+
+fn generate_report(cases: Vec<BacktestCaseResult>, options: &BacktestOptions) -> BacktestReport {
+    // Cases without final results would count every prediction as a miss
+    let scored_predictions: Vec<&CompetitorPrediction> = cases
+        .iter()
+        .filter(|case| case.error.is_none())
+        .flat_map(|case| &case.predictions)
+        .collect();
+
+    let mean = |metric: fn(&BacktestCaseResult) -> Option<f32>| {
+        let values: Vec<f32> = cases.iter().filter_map(metric).collect();
+        (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+    };
+
+    let format_metric = |value: Option<f32>| value.map_or(String::new(), |v| format!("{v:.6}"));
+
+    let mut csv = String::from(
+        "competition_id,event_id,num_competitors,log_loss,win_brier,podium_brier,spearman\n",
+    );
+
+    for case in &cases {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            case.competition_id,
+            case.event_id,
+            case.predictions.len(),
+            format_metric(case.log_loss),
+            format_metric(case.win_brier),
+            format_metric(case.podium_brier),
+            format_metric(case.spearman),
+        ));
+    }
+
+    BacktestReport {
+        mean_log_loss: mean(|case| case.log_loss),
+        mean_win_brier: mean(|case| case.win_brier),
+        mean_podium_brier: mean(|case| case.podium_brier),
+        mean_spearman: mean(|case| case.spearman),
+        calibration: calc_calibration(&scored_predictions, options.calibration_bins),
+        cases,
+        csv,
+    }
+}
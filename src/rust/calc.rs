@@ -1,4 +1,5 @@
-use std::f32::consts::PI;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{PI, SQRT_2};
 
 pub fn calc_weighted_mean_variance_stdev(data: &[(i32, f32)]) -> (f32, f32, f32) {
     if data.is_empty() {
@@ -34,13 +35,224 @@ pub fn calc_weighted_mean_variance_stdev(data: &[(i32, f32)]) -> (f32, f32, f32)
     (mean, variance, stdev)
 }
 
-pub fn trim_weighted_results(data: Vec<(i32, f32)>, mean: f32, stdev: f32) -> Vec<(i32, f32)> {
-    let threshold = (mean + stdev * 2.0) as i32;
+// Scales the median absolute deviation so it estimates the standard deviation of a normal distribution
+const MAD_SCALE: f32 = 1.4826;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    try_from = "UncheckedTrimStrategy"
+)]
+pub enum TrimStrategy {
+    // Keep every result
+    None,
+    // Drop results above mean + threshold * stdev
+    UpperSigma { threshold: f32 },
+    // Drop results more than threshold * stdev away from the mean on either side
+    Sigma { threshold: f32 },
+    // Drop results more than threshold scaled MADs away from the weighted median
+    Mad { threshold: f32 },
+    // Keep results between the lower and upper weighted quantiles
+    Quantile { lower: f32, upper: f32 },
+    // Keep every result, but down-weight those more than threshold scaled MADs from the median
+    Huber { threshold: f32 },
+}
+
+impl Default for TrimStrategy {
+    fn default() -> Self {
+        Self::UpperSigma { threshold: 2.0 }
+    }
+}
+
+// TrimStrategy as given, before checking that its settings can keep any results
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum UncheckedTrimStrategy {
+    None,
+    UpperSigma { threshold: f32 },
+    Sigma { threshold: f32 },
+    Mad { threshold: f32 },
+    Quantile { lower: f32, upper: f32 },
+    Huber { threshold: f32 },
+}
+
+impl TryFrom<UncheckedTrimStrategy> for TrimStrategy {
+    type Error = &'static str;
+
+    fn try_from(strategy: UncheckedTrimStrategy) -> Result<Self, Self::Error> {
+        let check_threshold = |threshold: f32| {
+            if threshold > 0.0 {
+                Ok(threshold)
+            } else {
+                Err("Trim threshold must be positive")
+            }
+        };
+
+        Ok(match strategy {
+            UncheckedTrimStrategy::None => Self::None,
+            UncheckedTrimStrategy::UpperSigma { threshold } => Self::UpperSigma {
+                threshold: check_threshold(threshold)?,
+            },
+            UncheckedTrimStrategy::Sigma { threshold } => Self::Sigma {
+                threshold: check_threshold(threshold)?,
+            },
+            UncheckedTrimStrategy::Mad { threshold } => Self::Mad {
+                threshold: check_threshold(threshold)?,
+            },
+            UncheckedTrimStrategy::Quantile { lower, upper } => {
+                if !(0.0 <= lower && lower < upper && upper <= 1.0) {
+                    return Err("Trim quantiles must satisfy 0 <= lower < upper <= 1");
+                }
+
+                Self::Quantile { lower, upper }
+            }
+            UncheckedTrimStrategy::Huber { threshold } => Self::Huber {
+                threshold: check_threshold(threshold)?,
+            },
+        })
+    }
+}
+
+// Kish's effective sample size of a set of weighted results
+pub fn calc_effective_sample_size(data: &[(i32, f32)]) -> f32 {
+    let total_weight: f32 = data.iter().map(|(_, w)| *w).sum();
+    let total_sq_weight: f32 = data.iter().map(|(_, w)| w.powi(2)).sum();
+
+    if total_sq_weight <= 0.0 {
+        return 0.0;
+    }
+
+    total_weight.powi(2) / total_sq_weight
+}
+
+// Returns the trimmed results along with the number of results that were dropped (or down-weighted
+// in the case of Huber weights). Falls back to the untrimmed results if trimming would leave
+// nothing to fit, e.g. when every result has the same value.
+pub fn trim_weighted_results(
+    data: &[(i32, f32)],
+    mean: f32,
+    stdev: f32,
+    strategy: TrimStrategy,
+) -> (Vec<(i32, f32)>, u32) {
+    let (trimmed, num_trimmed) = trim_with_strategy(data.to_vec(), mean, stdev, strategy);
+    let total_weight: f32 = trimmed.iter().map(|(_, w)| *w).sum();
+
+    if trimmed.is_empty() || total_weight <= 0.0 {
+        return (data.to_vec(), 0);
+    }
+
+    (trimmed, num_trimmed)
+}
+
+fn trim_with_strategy(
+    data: Vec<(i32, f32)>,
+    mean: f32,
+    stdev: f32,
+    strategy: TrimStrategy,
+) -> (Vec<(i32, f32)>, u32) {
+    let original_len = data.len();
+
+    let trimmed = match strategy {
+        TrimStrategy::None => data,
+        TrimStrategy::UpperSigma { threshold } => {
+            let upper = (mean + stdev * threshold) as i32;
+            retain_in_range(data, i32::MIN, upper)
+        }
+        TrimStrategy::Sigma { threshold } => {
+            let lower = (mean - stdev * threshold) as i32;
+            let upper = (mean + stdev * threshold) as i32;
+            retain_in_range(data, lower, upper)
+        }
+        TrimStrategy::Mad { threshold } => {
+            let (median, mad) = calc_weighted_median_mad(&data);
+
+            if mad == 0.0 {
+                return (data, 0);
+            }
+
+            let spread = MAD_SCALE * mad * threshold;
+            retain_in_range(data, (median - spread) as i32, (median + spread) as i32)
+        }
+        TrimStrategy::Quantile { lower, upper } => {
+            let lower = calc_weighted_quantile(&data, lower);
+            let upper = calc_weighted_quantile(&data, upper);
+            retain_in_range(data, lower, upper)
+        }
+        TrimStrategy::Huber { threshold } => return apply_huber_weights(data, threshold),
+    };
+
+    let num_trimmed = (original_len - trimmed.len()) as u32;
+
+    (trimmed, num_trimmed)
+}
+
+fn retain_in_range(data: Vec<(i32, f32)>, lower: i32, upper: i32) -> Vec<(i32, f32)> {
     data.into_iter()
-        .filter(|&(val, _)| val <= threshold)
+        .filter(|&(val, _)| val >= lower && val <= upper)
         .collect()
 }
 
+fn apply_huber_weights(data: Vec<(i32, f32)>, threshold: f32) -> (Vec<(i32, f32)>, u32) {
+    let (median, mad) = calc_weighted_median_mad(&data);
+
+    if mad == 0.0 {
+        return (data, 0);
+    }
+
+    let scale = MAD_SCALE * mad;
+    let mut num_downweighted = 0;
+
+    let reweighted = data
+        .into_iter()
+        .map(|(val, weight)| {
+            let z = ((val as f32 - median) / scale).abs();
+
+            if z > threshold {
+                num_downweighted += 1;
+                (val, weight * threshold / z)
+            } else {
+                (val, weight)
+            }
+        })
+        .collect();
+
+    (reweighted, num_downweighted)
+}
+
+pub fn calc_weighted_quantile(data: &[(i32, f32)], quantile: f32) -> i32 {
+    let mut sorted = data.to_vec();
+    sorted.sort_unstable_by_key(|&(val, _)| val);
+
+    let total_weight: f32 = sorted.iter().map(|(_, w)| *w).sum();
+    let target = total_weight * quantile.clamp(0.0, 1.0);
+
+    let mut cumulative_weight = 0.0;
+
+    for &(val, weight) in &sorted {
+        cumulative_weight += weight;
+
+        if cumulative_weight >= target {
+            return val;
+        }
+    }
+
+    sorted.last().map_or(0, |&(val, _)| val)
+}
+
+fn calc_weighted_median_mad(data: &[(i32, f32)]) -> (f32, f32) {
+    let median = calc_weighted_quantile(data, 0.5);
+
+    let deviations: Vec<(i32, f32)> = data
+        .iter()
+        .map(|&(val, weight)| ((val - median).abs(), weight))
+        .collect();
+
+    let mad = calc_weighted_quantile(&deviations, 0.5);
+
+    (median as f32, mad as f32)
+}
+
 pub fn fit_weighted_skewnorm(data: &[(i32, f32)]) -> (f32, f32, f32) {
     let (mean, variance, stdev) = calc_weighted_mean_variance_stdev(data);
 
@@ -74,6 +286,162 @@ pub fn fit_weighted_skewnorm(data: &[(i32, f32)]) -> (f32, f32, f32) {
     (alpha, omega, xi)
 }
 
+// Abramowitz and Stegun approximation 7.1.26, accurate to about 1.5e-7
+fn erf(x: f32) -> f32 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly = t
+        * (0.254_829_6
+            + t * (-0.284_496_72 + t * (1.421_413_8 + t * (-1.453_152_1 + t * 1.061_405_4))));
+
+    x.signum() * (1.0 - poly * (-x * x).exp())
+}
+
+pub fn normal_cdf(z: f32) -> f32 {
+    0.5 * (1.0 + erf(z / SQRT_2))
+}
+
+// Owen's T function, evaluated with Simpson's rule. The skew parameters we fit are bounded, so a
+// fixed number of intervals is accurate enough.
+fn owens_t(h: f32, a: f32) -> f32 {
+    const INTERVALS: usize = 32;
+
+    let integrand = |x: f32| (-0.5 * h * h * (1.0 + x * x)).exp() / (1.0 + x * x);
+    let step = a / INTERVALS as f32;
+
+    let sum = (0..=INTERVALS)
+        .map(|i| {
+            let coefficient = match i {
+                0 => 1.0,
+                _ if i == INTERVALS => 1.0,
+                _ if i % 2 == 1 => 4.0,
+                _ => 2.0,
+            };
+
+            coefficient * integrand(i as f32 * step)
+        })
+        .sum::<f32>();
+
+    sum * step / (3.0 * 2.0 * PI)
+}
+
+pub fn skewnorm_pdf(x: f32, skew: f32, shape: f32, location: f32) -> f32 {
+    let z = (x - location) / shape;
+    let normal_pdf = (-0.5 * z * z).exp() / (2.0 * PI).sqrt();
+
+    2.0 / shape * normal_pdf * normal_cdf(skew * z)
+}
+
+pub fn skewnorm_cdf(x: f32, skew: f32, shape: f32, location: f32) -> f32 {
+    let z = (x - location) / shape;
+
+    (normal_cdf(z) - 2.0 * owens_t(z, skew)).clamp(0.0, 1.0)
+}
+
+pub fn skewnorm_quantile(probability: f32, skew: f32, shape: f32, location: f32) -> f32 {
+    let mut low = location - 10.0 * shape;
+    let mut high = location + 10.0 * shape;
+
+    for _ in 0..50 {
+        let mid = 0.5 * (low + high);
+
+        if skewnorm_cdf(mid, skew, shape, location) < probability {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    0.5 * (low + high)
+}
+
+// z-score for a 95% confidence interval
+pub const Z_95: f32 = 1.96;
+
+pub fn calc_standard_error(successes: u32, trials: u32) -> f32 {
+    if trials == 0 {
+        return 0.0;
+    }
+
+    let p = successes as f32 / trials as f32;
+
+    (p * (1.0 - p) / trials as f32).sqrt()
+}
+
+// Wilson score interval for a binomial proportion
+pub fn calc_wilson_interval(successes: u32, trials: u32, z: f32) -> (f32, f32) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+
+    let n = trials as f32;
+    let p = successes as f32 / n;
+    let z2 = z.powi(2);
+
+    let denominator = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denominator;
+    let half_width = z / denominator * (p * (1.0 - p) / n + z2 / (4.0 * n.powi(2))).sqrt();
+
+    (
+        (center - half_width).max(0.0),
+        (center + half_width).min(1.0),
+    )
+}
+
+// Spearman's rank correlation, using average ranks for ties
+pub fn calc_spearman_correlation(a: &[f32], b: &[f32]) -> Option<f32> {
+    if a.len() != b.len() || a.len() < 2 {
+        return None;
+    }
+
+    let (ranks_a, ranks_b) = (calc_average_ranks(a), calc_average_ranks(b));
+    let n = a.len() as f32;
+
+    let mean = (n + 1.0) / 2.0;
+    let (covariance, variance_a, variance_b) = ranks_a.iter().zip(&ranks_b).fold(
+        (0.0, 0.0, 0.0),
+        |(covariance, variance_a, variance_b), (rank_a, rank_b)| {
+            (
+                covariance + (rank_a - mean) * (rank_b - mean),
+                variance_a + (rank_a - mean).powi(2),
+                variance_b + (rank_b - mean).powi(2),
+            )
+        },
+    );
+
+    if variance_a == 0.0 || variance_b == 0.0 {
+        return None;
+    }
+
+    Some(covariance / (variance_a * variance_b).sqrt())
+}
+
+// 1-based ranks, where tied values share the average of their ranks
+fn calc_average_ranks(values: &[f32]) -> Vec<f32> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&i, &j| values[i].total_cmp(&values[j]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+
+    while start < order.len() {
+        let mut end = start + 1;
+
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+
+        let average_rank = (start + end + 1) as f32 / 2.0;
+
+        for &i in &order[start..end] {
+            ranks[i] = average_rank;
+        }
+
+        start = end;
+    }
+
+    ranks
+}
+
 pub fn find_lowest_indices(vec: &[i32]) -> Vec<usize> {
     let mut indices: Vec<usize> = (0..vec.len()).collect();
     indices.sort_unstable_by_key(|&i| vec[i]);
@@ -0,0 +1,128 @@
+use serde::Serialize;
+
+use crate::backtest::CompetitorPrediction;
+
+#[derive(Serialize, Debug)]
+pub struct CalibrationBin {
+    lower: f32,
+    upper: f32,
+    count: u32,
+    // None for empty bins
+    mean_predicted: Option<f32>,
+    observed_frequency: Option<f32>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ReliabilityCurve {
+    bins: Vec<CalibrationBin>,
+    // Count-weighted mean gap between predicted and observed frequencies, and the largest gap
+    expected_calibration_error: f32,
+    max_calibration_error: f32,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CalibrationReport {
+    num_predictions: u32,
+    win: ReliabilityCurve,
+    podium: ReliabilityCurve,
+    csv: String,
+}
+
+// Predictions are grouped into equal-width probability bins, and each bin's mean prediction is
+// compared with how often the outcome actually happened
+pub fn calc_calibration(
+    predictions: &[&CompetitorPrediction],
+    num_bins: usize,
+) -> CalibrationReport {
+    let num_bins = num_bins.max(1);
+
+    let win = calc_reliability_curve(
+        predictions
+            .iter()
+            .map(|p| (p.win_probability, p.actual_position == Some(1))),
+        num_bins,
+    );
+    let podium = calc_reliability_curve(
+        predictions.iter().map(|p| {
+            (
+                p.podium_probability,
+                p.actual_position.is_some_and(|pos| pos <= 3),
+            )
+        }),
+        num_bins,
+    );
+
+    let mut csv = String::from("outcome,lower,upper,count,mean_predicted,observed_frequency\n");
+
+    for (outcome, curve) in [("win", &win), ("podium", &podium)] {
+        for bin in &curve.bins {
+            csv.push_str(&format!(
+                "{},{:.4},{:.4},{},{},{}\n",
+                outcome,
+                bin.lower,
+                bin.upper,
+                bin.count,
+                bin.mean_predicted
+                    .map_or(String::new(), |v| format!("{v:.6}")),
+                bin.observed_frequency
+                    .map_or(String::new(), |v| format!("{v:.6}")),
+            ));
+        }
+    }
+
+    CalibrationReport {
+        num_predictions: predictions.len() as u32,
+        win,
+        podium,
+        csv,
+    }
+}
+
+fn calc_reliability_curve(
+    outcomes: impl Iterator<Item = (f32, bool)>,
+    num_bins: usize,
+) -> ReliabilityCurve {
+    // Per bin: count, sum of predicted probabilities and number of times the outcome happened
+    let mut totals = vec![(0u32, 0.0f32, 0u32); num_bins];
+
+    for (probability, happened) in outcomes {
+        let bin = ((probability * num_bins as f32) as usize).min(num_bins - 1);
+
+        totals[bin].0 += 1;
+        totals[bin].1 += probability;
+        totals[bin].2 += happened as u32;
+    }
+
+    let total_count: u32 = totals.iter().map(|(count, _, _)| count).sum();
+
+    let bins: Vec<CalibrationBin> = totals
+        .iter()
+        .enumerate()
+        .map(|(i, &(count, predicted_sum, happened))| CalibrationBin {
+            lower: i as f32 / num_bins as f32,
+            upper: (i + 1) as f32 / num_bins as f32,
+            count,
+            mean_predicted: (count > 0).then(|| predicted_sum / count as f32),
+            observed_frequency: (count > 0).then(|| happened as f32 / count as f32),
+        })
+        .collect();
+
+    let gaps = bins.iter().filter_map(|bin| {
+        let gap = (bin.mean_predicted? - bin.observed_frequency?).abs();
+        Some((bin.count, gap))
+    });
+
+    let (expected_calibration_error, max_calibration_error) =
+        gaps.fold((0.0, 0.0f32), |(expected, max), (count, gap)| {
+            (
+                expected + count as f32 / total_count.max(1) as f32 * gap,
+                max.max(gap),
+            )
+        });
+
+    ReliabilityCurve {
+        bins,
+        expected_calibration_error,
+        max_calibration_error,
+    }
+}
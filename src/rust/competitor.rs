@@ -1,52 +1,216 @@
 use serde::{Deserialize, Serialize};
-use std::f32::consts::LN_2;
+use std::f32::consts::{LN_2, PI};
 
 use crate::{
-    calc::{calc_weighted_mean_variance_stdev, fit_weighted_skewnorm, trim_weighted_results},
+    calc::{
+        calc_effective_sample_size, calc_weighted_mean_variance_stdev, fit_weighted_skewnorm,
+        skewnorm_quantile, trim_weighted_results, TrimStrategy,
+    },
+    diagnostics::{calc_fit_diagnostics, FitDiagnostics},
+    move_count::MoveCountModel,
+    penalty::DNS_RESULT,
+    prior::{CrossEventPriorOptions, EventPrior},
+    records::{RecordPair, RecordThresholds},
     simd::DNF_VALUE,
+    unofficial::UnofficialResults,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DatedCompetitionResult {
     pub days_since: i32,
+    pub date: String, // YYYY-MM-DD
     pub results: Vec<i32>,
+    #[serde(default)]
+    pub best_average: Option<i32>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ModelOptions {
+    pub trim: TrimStrategy,
+    // Enables priors estimated from related events when set
+    pub cross_event_prior: Option<CrossEventPriorOptions>,
+}
+
+// Manual adjustments applied on top of a competitor's fitted model
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct StatsOverride {
+    // Added to every simulated result, in centiseconds
+    pub mean_shift: Option<f32>,
+    // Scales the spread of the distribution while keeping its mean fixed
+    pub spread_multiplier: Option<f32>,
+    pub dnf_rate: Option<f32>,
+    pub plus_two_rate: Option<f32>,
+}
+
+impl StatsOverride {
+    fn apply(&self, stats: &CompetitorStats) -> CompetitorStats {
+        let mut adjusted = stats.clone();
+
+        if let Some(multiplier) = self.spread_multiplier {
+            adjusted.set_model_moments(
+                stats.model_mean(),
+                stats.model_variance() * multiplier.powi(2),
+            );
+            adjusted.mean = stats.mean;
+            adjusted.stdev = stats.stdev * multiplier;
+        }
+
+        if let Some(shift) = self.mean_shift {
+            adjusted.location += shift;
+            adjusted.mean += shift;
+        }
+
+        if let Some(dnf_rate) = self.dnf_rate {
+            adjusted.dnf_rate = dnf_rate.clamp(0.0, 1.0);
+        }
+
+        if let Some(plus_two_rate) = self.plus_two_rate {
+            adjusted.plus_two_rate = plus_two_rate.clamp(0.0, 1.0);
+        }
+
+        adjusted
+    }
 }
 
 pub struct Competitor {
+    pub wca_id: String,
     pub name: String,
+    // ISO 3166-1 alpha-2 code, when known
+    pub country: Option<String>,
     pub results: Vec<DatedCompetitionResult>,
     pub entered_results: Vec<i32>,
+    pub unofficial_results: Option<UnofficialResults>,
     pub stats: Option<CompetitorStats>,
+    pub stats_override: Option<StatsOverride>,
+    adjusted_stats: Option<CompetitorStats>,
+    pub move_count_model: Option<MoveCountModel>,
+    pub records: RecordThresholds,
+    counts_moves: bool,
+    prior: Option<EventPrior>,
+    halflife: f32,
+    options: ModelOptions,
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Debug, Clone)]
 pub struct CompetitorStats {
     pub location: f32,
     pub shape: f32,
     pub skew: f32,
     pub dnf_rate: f32,
+    // Extra chance of a +2 on a completed attempt, added on top of the fitted distribution.
+    // Official times are fitted with their penalties included, while home times are fitted
+    // without them, so this comes from the home solves' share of the fit.
+    pub plus_two_rate: f32,
     pub mean: f32,
     pub stdev: f32,
     pub num_non_dnf_results: u32,
+    pub effective_sample_size: f32,
+    pub num_trimmed: u32,
+    pub prior: Option<EventPrior>,
+}
+
+impl CompetitorStats {
+    fn skew_delta(&self) -> f32 {
+        self.skew / (1.0 + self.skew.powi(2)).sqrt()
+    }
+
+    // Mean of the fitted skew-normal distribution
+    pub fn model_mean(&self) -> f32 {
+        self.location + self.shape * self.skew_delta() * (2.0 / PI).sqrt()
+    }
+
+    // Variance of the fitted skew-normal distribution
+    pub fn model_variance(&self) -> f32 {
+        self.shape.powi(2) * (1.0 - 2.0 * self.skew_delta().powi(2) / PI)
+    }
+
+    // Moves the location and shape so the distribution has the given moments, keeping the skew
+    pub fn set_model_moments(&mut self, mean: f32, variance: f32) {
+        let delta = self.skew_delta();
+
+        self.shape = (variance / (1.0 - 2.0 * delta.powi(2) / PI)).sqrt();
+        self.location = mean - self.shape * delta * (2.0 / PI).sqrt();
+        self.mean = mean;
+        self.stdev = variance.sqrt();
+    }
 }
 
 impl Competitor {
-    pub fn new(name: String, results: Vec<DatedCompetitionResult>, halflife: f32) -> Self {
-        let stats = Competitor::calculate_stats(&results, halflife);
+    pub fn new(
+        wca_id: String,
+        name: String,
+        results: Vec<DatedCompetitionResult>,
+        halflife: f32,
+        options: &ModelOptions,
+    ) -> Self {
+        let stats = Competitor::calculate_stats(&results, None, None, halflife, options);
+        let personal_records = Competitor::calculate_personal_records(&results);
 
         Self {
+            wca_id,
             name,
+            country: None,
             results,
             entered_results: vec![],
+            unofficial_results: None,
             stats,
+            stats_override: None,
+            adjusted_stats: None,
+            move_count_model: None,
+            records: RecordThresholds {
+                personal: personal_records,
+                ..Default::default()
+            },
+            counts_moves: false,
+            prior: None,
+            halflife,
+            options: options.clone(),
+        }
+    }
+
+    // Personal records within the given results, ignoring unofficial results. The data loader
+    // replaces these with records from every fetched result, including ones outside the time range.
+    fn calculate_personal_records(results: &[DatedCompetitionResult]) -> RecordPair {
+        RecordPair {
+            single: results
+                .iter()
+                .flat_map(|result_set| &result_set.results)
+                .copied()
+                .filter(|&solve| solve > 0)
+                .min(),
+            average: results
+                .iter()
+                .filter_map(|result_set| result_set.best_average)
+                .min(),
         }
     }
 
     fn calculate_stats(
         results: &[DatedCompetitionResult],
+        unofficial_results: Option<&UnofficialResults>,
+        prior: Option<&EventPrior>,
+        halflife: f32,
+        options: &ModelOptions,
+    ) -> Option<CompetitorStats> {
+        let stats = Self::calculate_fitted_stats(results, unofficial_results, halflife, options);
+
+        match (stats, prior) {
+            (Some(stats), Some(prior)) if prior.applies_to(&stats) => Some(prior.blend(&stats)),
+            (None, Some(prior)) => Some(prior.to_stats()),
+            (stats, _) => stats,
+        }
+    }
+
+    fn calculate_fitted_stats(
+        results: &[DatedCompetitionResult],
+        unofficial_results: Option<&UnofficialResults>,
         halflife: f32,
+        options: &ModelOptions,
     ) -> Option<CompetitorStats> {
-        let weighted_results = Self::apply_exponential_weights(results, halflife);
+        let weighted_results =
+            Self::calculate_weighted_results(results, unofficial_results, halflife);
 
         if weighted_results.is_empty() {
             return None;
@@ -80,15 +244,29 @@ impl Competitor {
             return None;
         }
 
+        let plus_two_rate = Self::calculate_plus_two_rate(
+            unofficial_results,
+            halflife,
+            non_dnf_weighted_results
+                .iter()
+                .map(|&(_, weight)| weight)
+                .sum(),
+        );
+
         let num_non_dnf_results = non_dnf_weighted_results.len() as u32;
+        let effective_sample_size = calc_effective_sample_size(&non_dnf_weighted_results);
 
         // Calculate weighted statistics
         let (sample_mean, _sample_variance, sample_dev) =
             calc_weighted_mean_variance_stdev(&non_dnf_weighted_results);
 
         // Trim outliers
-        let trimmed_weighted_results =
-            trim_weighted_results(non_dnf_weighted_results, sample_mean, sample_dev);
+        let (trimmed_weighted_results, num_trimmed) = trim_weighted_results(
+            &non_dnf_weighted_results,
+            sample_mean,
+            sample_dev,
+            options.trim,
+        );
 
         // Fit distribution
         let (skew, shape, location) = fit_weighted_skewnorm(&trimmed_weighted_results);
@@ -98,12 +276,99 @@ impl Competitor {
             shape,
             skew,
             dnf_rate,
+            plus_two_rate,
             mean: sample_mean,
             stdev: sample_dev,
             num_non_dnf_results,
+            effective_sample_size,
+            num_trimmed,
+            prior: None,
         })
     }
 
+    fn calculate_weighted_results(
+        results: &[DatedCompetitionResult],
+        unofficial_results: Option<&UnofficialResults>,
+        halflife: f32,
+    ) -> Vec<(i32, f32)> {
+        let mut weighted_results = Self::apply_exponential_weights(results, halflife);
+
+        // Blend in home results, down-weighted by how much we trust them
+        if let Some(unofficial) = unofficial_results {
+            let weighted_unofficial =
+                Self::apply_exponential_weights(&unofficial.results, halflife)
+                    .into_iter()
+                    .map(|(val, weight)| {
+                        let val = if val > 0 {
+                            unofficial.adjust_time(val)
+                        } else {
+                            val
+                        };
+
+                        (val, weight * unofficial.trust_weight)
+                    });
+
+            weighted_results.extend(weighted_unofficial);
+        }
+
+        // A DNS isn't an attempt, so it says nothing about how likely a DNF is
+        weighted_results.retain(|&(val, _)| val != DNS_RESULT);
+
+        weighted_results
+    }
+
+    // Home +2 rate scaled by the home solves' share of the weight of completed attempts
+    fn calculate_plus_two_rate(
+        unofficial_results: Option<&UnofficialResults>,
+        halflife: f32,
+        non_dnf_weight: f32,
+    ) -> f32 {
+        let Some(unofficial) = unofficial_results else {
+            return 0.0;
+        };
+
+        if non_dnf_weight <= 0.0 {
+            return 0.0;
+        }
+
+        let home_weight: f32 = Self::apply_exponential_weights(&unofficial.results, halflife)
+            .into_iter()
+            .filter(|&(val, _)| val > 0)
+            .map(|(_, weight)| weight * unofficial.trust_weight)
+            .sum();
+
+        let rate = unofficial.plus_two_rate.unwrap_or(0.0);
+
+        (rate * home_weight / non_dnf_weight).clamp(0.0, 1.0)
+    }
+
+    // How well the fit (before any prior) describes all non-DNF results, including trimmed ones.
+    // This is only needed for display, so it's computed on request rather than with every fit.
+    pub fn fit_diagnostics(&self) -> Option<FitDiagnostics> {
+        let stats = Self::calculate_fitted_stats(
+            &self.results,
+            self.unofficial_results.as_ref(),
+            self.halflife,
+            &self.options,
+        )?;
+
+        let non_dnf_weighted_results: Vec<(i32, f32)> = Self::calculate_weighted_results(
+            &self.results,
+            self.unofficial_results.as_ref(),
+            self.halflife,
+        )
+        .into_iter()
+        .filter(|&(val, _)| val > 0)
+        .collect();
+
+        Some(calc_fit_diagnostics(
+            &non_dnf_weighted_results,
+            stats.skew,
+            stats.shape,
+            stats.location,
+        ))
+    }
+
     fn apply_exponential_weights(
         results: &[DatedCompetitionResult],
         halflife: f32,
@@ -123,6 +388,105 @@ impl Competitor {
         weighted_results
     }
 
+    pub fn set_unofficial_results(&mut self, unofficial_results: Option<UnofficialResults>) {
+        self.unofficial_results = unofficial_results;
+        self.refresh_stats();
+    }
+
+    pub fn set_prior(&mut self, prior: Option<EventPrior>) {
+        self.prior = prior;
+        self.refresh_stats();
+    }
+
+    // A copy refitted from the already loaded results with a different half-life, keeping only
+    // results from the last `window_days` days when set. Everything set on top of the fit (home
+    // results, prior, override, entered results and records) is carried over.
+    pub fn refit(&self, halflife: f32, window_days: Option<i32>) -> Self {
+        let in_window = |result_set: &&DatedCompetitionResult| {
+            window_days.is_none_or(|window_days| result_set.days_since <= window_days)
+        };
+
+        let results = self.results.iter().filter(in_window).cloned().collect();
+
+        let mut competitor = Self::new(
+            self.wca_id.clone(),
+            self.name.clone(),
+            results,
+            halflife,
+            &self.options,
+        );
+
+        competitor.country = self.country.clone();
+        competitor.entered_results = self.entered_results.clone();
+        competitor.records = self.records;
+        competitor.counts_moves = self.counts_moves;
+        competitor.prior = self.prior;
+        competitor.stats_override = self.stats_override.clone();
+        competitor.unofficial_results =
+            self.unofficial_results
+                .as_ref()
+                .map(|unofficial| UnofficialResults {
+                    results: unofficial
+                        .results
+                        .iter()
+                        .filter(in_window)
+                        .cloned()
+                        .collect(),
+                    ..unofficial.clone()
+                });
+
+        competitor.refresh_stats();
+
+        competitor
+    }
+
+    fn refresh_stats(&mut self) {
+        self.stats = Self::calculate_stats(
+            &self.results,
+            self.unofficial_results.as_ref(),
+            self.prior.as_ref(),
+            self.halflife,
+            &self.options,
+        );
+
+        // Re-apply any override on top of the refitted stats
+        let stats_override = self.stats_override.take();
+        self.set_stats_override(stats_override);
+    }
+
+    pub fn set_stats_override(&mut self, stats_override: Option<StatsOverride>) {
+        self.adjusted_stats = match (&self.stats, &stats_override) {
+            (Some(stats), Some(stats_override)) => Some(stats_override.apply(stats)),
+            _ => None,
+        };
+
+        self.stats_override = stats_override;
+        self.refresh_move_count_model();
+    }
+
+    // Switches the competitor to a discrete model over whole move counts (for FMC)
+    pub fn use_move_count_model(&mut self) {
+        self.counts_moves = true;
+        self.refresh_move_count_model();
+    }
+
+    fn refresh_move_count_model(&mut self) {
+        self.move_count_model = if self.counts_moves {
+            self.model_stats().and_then(MoveCountModel::from_stats)
+        } else {
+            None
+        };
+    }
+
+    // The stats used for simulation, with any manual override applied
+    pub fn model_stats(&self) -> Option<&CompetitorStats> {
+        self.adjusted_stats.as_ref().or(self.stats.as_ref())
+    }
+
+    pub fn is_overridden(&self) -> bool {
+        self.adjusted_stats.is_some()
+    }
+
     pub fn add_entered_results(&mut self, results: Vec<i32>) {
         self.entered_results = results;
     }
@@ -133,16 +497,46 @@ impl Competitor {
             .map_or(0, |stats| stats.num_non_dnf_results)
     }
 
+    pub fn get_num_trimmed(&self) -> u32 {
+        self.stats.as_ref().map_or(0, |stats| stats.num_trimmed)
+    }
+
+    pub fn set_regional_records(
+        &mut self,
+        national: RecordPair,
+        continental: RecordPair,
+        world: RecordPair,
+    ) {
+        self.records.national = national;
+        self.records.continental = continental;
+        self.records.world = world;
+    }
+
+    pub fn get_last_result_date(&self) -> Option<String> {
+        self.results
+            .iter()
+            .filter(|result_set| !result_set.results.is_empty())
+            .min_by_key(|result_set| result_set.days_since)
+            .map(|result_set| result_set.date.clone())
+    }
+
     pub fn get_mean(&self) -> u32 {
-        self.stats
-            .as_ref()
+        self.model_stats()
             .map_or(DNF_VALUE as u32, |stats| stats.mean as u32)
     }
 
+    pub fn get_interquartile_range(&self) -> Option<f32> {
+        let stats = self.model_stats()?;
+        let quantile =
+            |probability| skewnorm_quantile(probability, stats.skew, stats.shape, stats.location);
+
+        Some(quantile(0.75) - quantile(0.25))
+    }
+
     pub fn get_person_hist_bounds(&self) -> (i32, i32) {
-        if let Some(stats) = &self.stats {
-            let hist_min = ((stats.mean - stats.stdev * 4.0) / 10.0) as i32;
-            let hist_max = ((stats.mean + stats.stdev * 4.0) / 10.0) as i32;
+        if let Some(stats) = self.model_stats() {
+            let hist_min = (stats.mean - stats.stdev * 4.0) as i32;
+            let hist_max = (stats.mean + stats.stdev * 4.0) as i32;
 
             (hist_min, hist_max)
         } else {
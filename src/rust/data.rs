@@ -1,6 +1,8 @@
 use crate::{
-    competitor::{Competitor, DatedCompetitionResult},
+    competitor::{Competitor, DatedCompetitionResult, ModelOptions},
     event::{EventType, Mo3Event},
+    prior::{CrossEventPriorOptions, RatioModel},
+    records::RecordPair,
 };
 use chrono::{Datelike, TimeZone, Utc};
 use futures::future::join_all;
@@ -27,41 +29,73 @@ struct APIRequestCompetitionDate {
 #[derive(Deserialize, Debug)]
 struct APIRequestPerson {
     name: String,
+    #[serde(default)]
+    country: Option<String>,
     results: HashMap<String, HashMap<String, Vec<APIRequestCompetitionResult>>>,
 }
 
 #[derive(Deserialize, Debug)]
 struct APIRequestCompetitionResult {
     solves: Vec<i32>,
+    #[serde(default)]
+    average: i32,
+    #[serde(default)]
+    round: String,
+    position: Option<u32>,
 }
 
 pub struct ParsedCompetitionResult {
     pub id: String,
     pub results: Vec<i32>,
+    pub best_average: Option<i32>,
+    pub final_position: Option<u32>,
 }
 
+// Names of a final round in WCA results. "Semi Final" and "B Final" aren't finals.
+const FINAL_ROUND_NAMES: [&str; 2] = ["Final", "Combined Final"];
+
+pub type ResultsByEvent<T> = HashMap<EventType, Vec<T>>;
+
 pub struct ParsedPersonResult {
+    pub wca_id: String,
     pub name: String,
-    pub results: Vec<ParsedCompetitionResult>,
+    pub country: Option<String>,
+    pub results: ResultsByEvent<ParsedCompetitionResult>,
+}
+
+struct DatedPersonResult {
+    wca_id: String,
+    name: String,
+    country: Option<String>,
+    results: ResultsByEvent<DatedCompetitionResult>,
+    // From every fetched result, not just those in the time range
+    personal_records: HashMap<EventType, RecordPair>,
+}
+
+struct CompetitionDate {
+    days_since: i32,
+    date: String,
 }
 
 pub struct CompetitionDataManager {
     competitors: Vec<String>,
-    event: EventType,
+    // Events to build competitors for. Each person is only fetched once for all of them.
+    events: Vec<EventType>,
     start_date: i64,
     end_date: i64,
     halflife: f32,
+    model_options: ModelOptions,
     request_client: Client,
 }
 
-struct TimeRange {
+pub(crate) struct TimeRange {
     start_timestamp: i64,
     end_timestamp: i64,
     years: Vec<i32>,
 }
 
 impl TimeRange {
-    fn new(start_date_js: i64, end_date_js: i64) -> Self {
+    pub(crate) fn new(start_date_js: i64, end_date_js: i64) -> Self {
         // Convert from milliseconds to seconds since JavaScript uses milliseconds
         let start_timestamp = start_date_js / 1000;
         let end_timestamp = end_date_js / 1000;
@@ -89,11 +123,11 @@ impl TimeRange {
         }
     }
 
-    fn in_time_range(&self, time_utc: i64) -> bool {
+    pub(crate) fn in_time_range(&self, time_utc: i64) -> bool {
         time_utc > self.start_timestamp && time_utc < self.end_timestamp
     }
 
-    fn days_from_cutoff(&self, time_utc: i64) -> i32 {
+    pub(crate) fn days_from_cutoff(&self, time_utc: i64) -> i32 {
         const SECONDS_PER_DAY: i64 = 60 * 60 * 24;
 
         ((self.end_timestamp - time_utc) / SECONDS_PER_DAY) as i32
@@ -103,29 +137,56 @@ impl TimeRange {
 impl CompetitionDataManager {
     pub fn create(
         competitors: Vec<String>,
-        event: EventType,
+        events: Vec<EventType>,
         start_date: i64,
         end_date: i64,
         halflife: f32,
+        model_options: ModelOptions,
     ) -> Self {
         Self {
             competitors,
-            event,
+            events,
             start_date,
             end_date,
             halflife,
+            model_options,
             request_client: Client::new(),
         }
     }
 
-    pub async fn fetch_all(&self) -> Result<Vec<Competitor>, &'static str> {
+    // Every fetched person as a competitor in each event, in the order they were requested
+    pub async fn fetch_all(&self) -> Result<ResultsByEvent<Competitor>, &'static str> {
         let competitions = self.get_competition_data().await?;
         let results = self.get_solve_data().await?;
 
         Ok(self.join_data(competitions, results))
     }
 
-    async fn get_competition_data(&self) -> Result<HashMap<String, i32>, &'static str> {
+    // Also gets each competitor's final position in the first event at the given competition,
+    // which is usually outside the loaded time range
+    pub async fn fetch_all_with_final_positions(
+        &self,
+        competition_id: &str,
+    ) -> Result<(ResultsByEvent<Competitor>, Vec<Option<u32>>), &'static str> {
+        let competitions = self.get_competition_data().await?;
+        let results = self.get_solve_data().await?;
+
+        let positions = results
+            .iter()
+            .map(|person| {
+                person
+                    .results
+                    .get(&self.events[0])?
+                    .iter()
+                    .find(|competition| competition.id == competition_id)?
+                    .final_position
+            })
+            .collect();
+
+        Ok((self.join_data(competitions, results), positions))
+    }
+
+    async fn get_competition_data(&self) -> Result<HashMap<String, CompetitionDate>, &'static str> {
         let time_range = TimeRange::new(self.start_date, self.end_date);
 
         let futures: Vec<_> = time_range
@@ -141,7 +202,7 @@ impl CompetitionDataManager {
         &self,
         year: i32,
         time_range: &TimeRange,
-    ) -> Result<HashMap<String, i32>, &'static str> {
+    ) -> Result<HashMap<String, CompetitionDate>, &'static str> {
         let url = format!("https://raw.githubusercontent.com/robiningelbrecht/wca-rest-api/master/api/competitions/{year}.json");
 
         let response = self.fetch::<APIRequestCompetitions>(url).await?;
@@ -152,7 +213,7 @@ impl CompetitionDataManager {
         &self,
         competition_list: Vec<APIRequestCompetition>,
         time_range: &TimeRange,
-    ) -> Result<HashMap<String, i32>, &'static str> {
+    ) -> Result<HashMap<String, CompetitionDate>, &'static str> {
         competition_list
             .iter()
             .filter_map(|comp| {
@@ -164,7 +225,12 @@ impl CompetitionDataManager {
 
                 let days_since_comp = time_range.days_from_cutoff(comp_timestamp);
 
-                Some(Ok((comp.id.clone(), days_since_comp)))
+                let competition_date = CompetitionDate {
+                    days_since: days_since_comp,
+                    date: comp.date.from.clone(),
+                };
+
+                Some(Ok((comp.id.clone(), competition_date)))
             })
             .collect()
     }
@@ -186,8 +252,8 @@ impl CompetitionDataManager {
 
     fn merge_competition_results(
         &self,
-        results: Vec<Result<HashMap<String, i32>, &'static str>>,
-    ) -> Result<HashMap<String, i32>, &'static str> {
+        results: Vec<Result<HashMap<String, CompetitionDate>, &'static str>>,
+    ) -> Result<HashMap<String, CompetitionDate>, &'static str> {
         let mut all_competitions = HashMap::new();
 
         for result in results {
@@ -214,39 +280,84 @@ impl CompetitionDataManager {
     async fn fetch_competitor_data(
         &self,
         competitor: &str,
-    ) -> Result<(String, Vec<ParsedCompetitionResult>), &'static str> {
+    ) -> Result<ParsedPersonResult, &'static str> {
         let url = format!("https://raw.githubusercontent.com/robiningelbrecht/wca-rest-api/master/api/persons/{competitor}.json");
 
         let response = self.fetch::<APIRequestPerson>(url).await?;
-        let results = self.extract_competitor_results(&response);
+        let results = self
+            .loaded_events()
+            .into_iter()
+            .map(|event| (event, self.extract_competitor_results(&response, event)))
+            .collect();
 
-        Ok((response.name, results))
+        Ok(ParsedPersonResult {
+            wca_id: competitor.to_string(),
+            name: response.name,
+            country: response.country,
+            results,
+        })
+    }
+
+    // The target events, plus related events when cross-event priors are enabled
+    fn loaded_events(&self) -> Vec<EventType> {
+        let mut events = self.events.clone();
+
+        if self.model_options.cross_event_prior.is_some() {
+            for event in &self.events {
+                for related in event.related_events() {
+                    if !events.contains(related) {
+                        events.push(*related);
+                    }
+                }
+            }
+        }
+
+        events
     }
 
     fn extract_competitor_results(
         &self,
         response: &APIRequestPerson,
+        event: EventType,
     ) -> Vec<ParsedCompetitionResult> {
         response
             .results
             .iter()
             .filter_map(|(comp_id, rounds)| {
                 rounds
-                    .get(self.event.id())
+                    .get(event.id())
                     .map(|event_data| ParsedCompetitionResult {
                         id: comp_id.to_string(),
-                        results: self.process_event_data(event_data),
+                        results: self.process_event_data(event_data, event),
+                        // Averages are already stored in hundredths of a move for FMC
+                        best_average: event_data
+                            .iter()
+                            .map(|round| round.average)
+                            .filter(|&average| average > 0)
+                            .min(),
+                        final_position: event_data
+                            .iter()
+                            .find(|round| FINAL_ROUND_NAMES.contains(&round.round.as_str()))
+                            .and_then(|round| round.position),
                     })
             })
             .collect()
     }
 
-    fn process_event_data(&self, event_data: &[APIRequestCompetitionResult]) -> Vec<i32> {
+    fn process_event_data(
+        &self,
+        event_data: &[APIRequestCompetitionResult],
+        event: EventType,
+    ) -> Vec<i32> {
+        // Rounds with fewer attempts (Bo1/Bo2, or missed cutoffs) pad their solves with 0s, which
+        // aren't attempts at all
         event_data
             .iter()
             .flat_map(|round| &round.solves)
+            .filter(|&&solve| solve != 0)
             .map(|solve| {
-                if self.event == EventType::Mo3(Mo3Event::F333) {
+                // Only move counts are scaled, so DNF and DNS codes keep their values
+                if event == EventType::Mo3(Mo3Event::F333) && *solve > 0 {
                     solve * 100
                 } else {
                     *solve
@@ -257,11 +368,10 @@ impl CompetitionDataManager {
 
     fn collect_solve_results(
         &self,
-        results: Vec<Result<(String, Vec<ParsedCompetitionResult>), &'static str>>,
+        results: Vec<Result<ParsedPersonResult, &'static str>>,
     ) -> Result<Vec<ParsedPersonResult>, &'static str> {
         results
             .into_iter()
-            .map(|result| result.map(|(name, results)| ParsedPersonResult { name, results }))
             .collect::<Result<Vec<_>, _>>()
             .map_err(|_| "Error fetching competitor data")
     }
@@ -281,27 +391,161 @@ impl CompetitionDataManager {
 
     fn join_data(
         &self,
-        competitions: HashMap<String, i32>,
+        competitions: HashMap<String, CompetitionDate>,
         results: Vec<ParsedPersonResult>,
-    ) -> Vec<Competitor> {
-        results
+    ) -> ResultsByEvent<Competitor> {
+        let dated_results: Vec<DatedPersonResult> = results
             .into_iter()
-            .map(|competitor| {
-                let results = competitor
+            .map(|person| DatedPersonResult {
+                wca_id: person.wca_id,
+                name: person.name,
+                country: person.country,
+                personal_records: person
+                    .results
+                    .iter()
+                    .map(|(&event, results)| (event, Self::calc_personal_records(results)))
+                    .collect(),
+                results: person
                     .results
                     .into_iter()
-                    .filter_map(|competition| {
-                        let days_since = competitions.get(&competition.id)?;
+                    .map(|(event, results)| (event, Self::date_results(&competitions, results)))
+                    .collect(),
+            })
+            .collect();
 
-                        Some(DatedCompetitionResult {
-                            days_since: *days_since,
-                            results: competition.results,
-                        })
-                    })
-                    .collect::<Vec<_>>();
+        self.events
+            .iter()
+            .map(|&event| {
+                let mut competitors =
+                    self.build_competitors(&dated_results, event, &self.model_options);
+
+                if let Some(prior_options) = self.model_options.cross_event_prior {
+                    self.apply_cross_event_priors(
+                        event,
+                        &mut competitors,
+                        &dated_results,
+                        &prior_options,
+                    );
+                }
+
+                (event, competitors)
+            })
+            .collect()
+    }
 
-                Competitor::new(competitor.name, results, self.halflife)
+    fn build_competitors(
+        &self,
+        dated_results: &[DatedPersonResult],
+        event: EventType,
+        options: &ModelOptions,
+    ) -> Vec<Competitor> {
+        dated_results
+            .iter()
+            .map(|person| {
+                let results = person.results.get(&event).cloned().unwrap_or_default();
+
+                let mut competitor = Competitor::new(
+                    person.wca_id.clone(),
+                    person.name.clone(),
+                    results,
+                    self.halflife,
+                    options,
+                );
+                competitor.country = person.country.clone();
+                competitor.records.personal = person
+                    .personal_records
+                    .get(&event)
+                    .copied()
+                    .unwrap_or_default();
+
+                competitor
             })
             .collect()
     }
+
+    fn calc_personal_records(results: &[ParsedCompetitionResult]) -> RecordPair {
+        RecordPair {
+            single: results
+                .iter()
+                .flat_map(|competition| &competition.results)
+                .copied()
+                .filter(|&solve| solve > 0)
+                .min(),
+            average: results
+                .iter()
+                .filter_map(|competition| competition.best_average)
+                .min(),
+        }
+    }
+
+    fn date_results(
+        competitions: &HashMap<String, CompetitionDate>,
+        results: Vec<ParsedCompetitionResult>,
+    ) -> Vec<DatedCompetitionResult> {
+        results
+            .into_iter()
+            .filter_map(|competition| {
+                let competition_date = competitions.get(&competition.id)?;
+
+                Some(DatedCompetitionResult {
+                    days_since: competition_date.days_since,
+                    date: competition_date.date.clone(),
+                    results: competition.results,
+                    best_average: competition.best_average,
+                })
+            })
+            .collect()
+    }
+
+    // Fits a ratio model between each related event and the target event across all loaded
+    // competitors, then uses it to give thin competitors a prior from their related results
+    fn apply_cross_event_priors(
+        &self,
+        event: EventType,
+        competitors: &mut [Competitor],
+        dated_results: &[DatedPersonResult],
+        prior_options: &CrossEventPriorOptions,
+    ) {
+        let source_options = ModelOptions {
+            cross_event_prior: None,
+            ..self.model_options.clone()
+        };
+
+        let sources: Vec<(RatioModel, Vec<Competitor>)> = event
+            .related_events()
+            .iter()
+            .filter_map(|&source_event| {
+                let source_competitors =
+                    self.build_competitors(dated_results, source_event, &source_options);
+
+                let pairs: Vec<_> = competitors
+                    .iter()
+                    .zip(&source_competitors)
+                    .filter_map(|(target, source)| {
+                        Some((target.stats.as_ref()?, source.stats.as_ref()?))
+                    })
+                    .collect();
+
+                let model = RatioModel::fit(&pairs)?;
+
+                Some((model, source_competitors))
+            })
+            .collect();
+
+        for (i, competitor) in competitors.iter_mut().enumerate() {
+            let prior = sources
+                .iter()
+                .filter_map(|(model, source_competitors)| {
+                    let source_stats = source_competitors[i].stats.as_ref()?;
+                    Some((model, source_stats))
+                })
+                // min_by keeps the first of equal spreads, so ties go to the earlier related event
+                .min_by(|(a, _), (b, _)| a.spread().total_cmp(&b.spread()))
+                .map(|(model, source_stats)| model.predict(source_stats, prior_options));
+
+            if prior.is_some() {
+                competitor.set_prior(prior);
+            }
+        }
+    }
 }
@@ -0,0 +1,155 @@
+use serde::Serialize;
+
+use crate::calc::{
+    calc_effective_sample_size, calc_weighted_quantile, skewnorm_cdf, skewnorm_quantile,
+};
+
+const QQ_MAX_POINTS: usize = 50;
+const COMPARED_QUANTILES: [f32; 5] = [0.05, 0.25, 0.5, 0.75, 0.95];
+
+// Asymptotic critical values of the scaled KS statistic at the 5% and 1% levels
+const KS_CRITICAL_GOOD: f32 = 1.36;
+const KS_CRITICAL_FAIR: f32 = 1.63;
+
+// Keeps the logarithms in the Anderson-Darling integral finite
+const CDF_EPSILON: f32 = 1e-6;
+
+#[derive(Serialize, Debug, Copy, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FitQuality {
+    Good,
+    Fair,
+    Poor,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct QQPoint {
+    pub theoretical: f32,
+    pub observed: f32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct QuantileComparison {
+    pub probability: f32,
+    pub observed: f32,
+    pub fitted: f32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FitDiagnostics {
+    pub ks_statistic: f32,
+    pub anderson_darling: f32,
+    pub effective_sample_size: f32,
+    pub quality: FitQuality,
+    pub qq_points: Vec<QQPoint>,
+    pub quantiles: Vec<QuantileComparison>,
+}
+
+// Compares weighted (non-DNF) results against a fitted skew-normal distribution
+pub fn calc_fit_diagnostics(
+    data: &[(i32, f32)],
+    skew: f32,
+    shape: f32,
+    location: f32,
+) -> FitDiagnostics {
+    let cdf = |x: f32| skewnorm_cdf(x, skew, shape, location);
+    let quantile = |p: f32| skewnorm_quantile(p, skew, shape, location);
+
+    let points = merge_ties(data);
+    let total_weight: f32 = points.iter().map(|(_, w)| *w).sum();
+    let effective_sample_size = calc_effective_sample_size(data);
+
+    let mut ks_statistic: f32 = 0.0;
+    let mut anderson_darling = 0.0;
+
+    let mut prev_ecdf = 0.0;
+    let mut prev_cdf = 0.0;
+
+    // The empirical CDF is a step function, so the Anderson-Darling integral can be evaluated
+    // exactly over each step
+    for &(val, weight) in &points {
+        let fitted = cdf(val as f32).clamp(CDF_EPSILON, 1.0 - CDF_EPSILON);
+        let ecdf = prev_ecdf + weight / total_weight;
+
+        ks_statistic = ks_statistic
+            .max((fitted - prev_ecdf).abs())
+            .max((ecdf - fitted).abs());
+
+        anderson_darling += anderson_darling_step(prev_ecdf, prev_cdf, fitted);
+
+        prev_ecdf = ecdf;
+        prev_cdf = fitted;
+    }
+
+    anderson_darling += anderson_darling_step(1.0, prev_cdf, 1.0);
+    anderson_darling *= effective_sample_size;
+
+    let scaled_ks = ks_statistic * effective_sample_size.sqrt();
+    let quality = if scaled_ks < KS_CRITICAL_GOOD {
+        FitQuality::Good
+    } else if scaled_ks < KS_CRITICAL_FAIR {
+        FitQuality::Fair
+    } else {
+        FitQuality::Poor
+    };
+
+    let num_qq_points = points.len().min(QQ_MAX_POINTS);
+    let qq_points = (0..num_qq_points)
+        .map(|i| {
+            let probability = (i as f32 + 0.5) / num_qq_points as f32;
+
+            QQPoint {
+                theoretical: quantile(probability),
+                observed: calc_weighted_quantile(data, probability) as f32,
+            }
+        })
+        .collect();
+
+    let quantiles = COMPARED_QUANTILES
+        .iter()
+        .map(|&probability| QuantileComparison {
+            probability,
+            observed: calc_weighted_quantile(data, probability) as f32,
+            fitted: quantile(probability),
+        })
+        .collect();
+
+    FitDiagnostics {
+        ks_statistic,
+        anderson_darling,
+        effective_sample_size,
+        quality,
+        qq_points,
+        quantiles,
+    }
+}
+
+// Integral of (ecdf - u)^2 / (u * (1 - u)) over u from cdf_start to cdf_end, with ecdf held constant
+fn anderson_darling_step(ecdf: f32, cdf_start: f32, cdf_end: f32) -> f32 {
+    let start = cdf_start.clamp(CDF_EPSILON, 1.0 - CDF_EPSILON);
+    let end = cdf_end.clamp(CDF_EPSILON, 1.0 - CDF_EPSILON);
+
+    if end <= start {
+        return 0.0;
+    }
+
+    ecdf.powi(2) * (end / start).ln()
+        - (1.0 - ecdf).powi(2) * ((1.0 - end) / (1.0 - start)).ln()
+        - (end - start)
+}
+
+fn merge_ties(data: &[(i32, f32)]) -> Vec<(i32, f32)> {
+    let mut sorted = data.to_vec();
+    sorted.sort_unstable_by_key(|&(val, _)| val);
+
+    let mut merged: Vec<(i32, f32)> = Vec::with_capacity(sorted.len());
+
+    for (val, weight) in sorted {
+        match merged.last_mut() {
+            Some((last_val, last_weight)) if *last_val == val => *last_weight += weight,
+            _ => merged.push((val, weight)),
+        }
+    }
+
+    merged
+}
@@ -1,11 +1,11 @@
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum EventType {
     Ao5(Ao5Event),
     Mo3(Mo3Event),
     Bo3(Bo3Event),
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Ao5Event {
     S222,
     S333,
@@ -19,14 +19,14 @@ pub enum Ao5Event {
     SQ1,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Mo3Event {
     S666,
     S777,
     F333,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Bo3Event {
     B333,
     B444,
@@ -88,6 +88,37 @@ impl EventType {
         }
     }
 
+    // Events whose results are informative about this event. The prior comes from whichever has
+    // the tightest ratio model, and this order only breaks ties.
+    pub fn related_events(&self) -> &'static [EventType] {
+        match self {
+            Self::Ao5(Ao5Event::S222) => &[Self::Ao5(Ao5Event::S333)],
+            Self::Ao5(Ao5Event::S333) => &[Self::Ao5(Ao5Event::S333oh), Self::Ao5(Ao5Event::S222)],
+            Self::Ao5(Ao5Event::S444) => &[Self::Ao5(Ao5Event::S555), Self::Ao5(Ao5Event::S333)],
+            Self::Ao5(Ao5Event::S555) => &[Self::Ao5(Ao5Event::S444), Self::Mo3(Mo3Event::S666)],
+            Self::Ao5(Ao5Event::S333oh) => &[Self::Ao5(Ao5Event::S333)],
+            Self::Ao5(Ao5Event::Megaminx) => {
+                &[Self::Ao5(Ao5Event::S555), Self::Ao5(Ao5Event::S333)]
+            }
+            Self::Ao5(Ao5Event::Pyraminx) => {
+                &[Self::Ao5(Ao5Event::S222), Self::Ao5(Ao5Event::Skewb)]
+            }
+            Self::Ao5(Ao5Event::Clock) => &[],
+            Self::Ao5(Ao5Event::Skewb) => {
+                &[Self::Ao5(Ao5Event::S222), Self::Ao5(Ao5Event::Pyraminx)]
+            }
+            Self::Ao5(Ao5Event::SQ1) => &[Self::Ao5(Ao5Event::S333)],
+
+            Self::Mo3(Mo3Event::S666) => &[Self::Mo3(Mo3Event::S777), Self::Ao5(Ao5Event::S555)],
+            Self::Mo3(Mo3Event::S777) => &[Self::Mo3(Mo3Event::S666)],
+            Self::Mo3(Mo3Event::F333) => &[],
+
+            Self::Bo3(Bo3Event::B333) => &[Self::Ao5(Ao5Event::S333)],
+            Self::Bo3(Bo3Event::B444) => &[Self::Bo3(Bo3Event::B333), Self::Bo3(Bo3Event::B555)],
+            Self::Bo3(Bo3Event::B555) => &[Self::Bo3(Bo3Event::B444)],
+        }
+    }
+
     pub fn num_attempts(&self) -> usize {
         match self {
             Self::Ao5(_) => 5,
@@ -4,17 +4,25 @@ use crate::competitor::Competitor;
 use crate::event::Mo3Event;
 use crate::histogram::Histogram;
 use crate::simd::{
-    calc_wca_average_5, calc_wca_best_3, calc_wca_mean_3, generate_skewnorm_vec, i32x4_to_slice,
-    i32x4_truncate_down_100, DNF_VALUE,
+    calc_wca_average_5, calc_wca_best_3, calc_wca_mean_3, generate_move_count_vec,
+    generate_skewnorm_vec, i32x4_to_slice, DNF_VALUE,
 };
 use crate::simulation::{ResultHistograms, RuntimeConfig};
 use core::arch::wasm32::v128;
-use std::iter;
 
 const AO5_SOLVE_COUNT: usize = 5;
 const MO3_SOLVE_COUNT: usize = 3;
 const BO3_SOLVE_COUNT: usize = 3;
 
+// Results of one round for 4 scenarios
+pub struct SimulatedRound {
+    // The result competitors are ranked by
+    pub result: [i32; 4],
+    pub average: [i32; 4],
+    pub best_single: [i32; 4],
+    pub solves: Vec<[i32; 4]>,
+}
+
 pub trait EventSimulation {
     fn generate_solves(
         &self,
@@ -25,31 +33,55 @@ pub trait EventSimulation {
 
     fn calculate_result(&self, solves: &[v128]) -> [i32; 4];
 
+    // The WCA average of the attempts, which is only different from the result for best-of events
+    fn calculate_average(&self, solves: &[v128]) -> [i32; 4] {
+        self.calculate_result(solves)
+    }
+
     fn run_simulation(
         &mut self,
         competitor: &Competitor,
         config: &mut RuntimeConfig,
         rng: &mut ThreadRng,
         histograms: &mut ResultHistograms,
-    ) -> [i32; 4] {
+    ) -> SimulatedRound {
         let solves: Vec<v128> = self.generate_solves(competitor, config, rng);
 
-        for (&solve, entered) in iter::zip(&solves, &competitor.entered_results) {
-            let solve_values = i32x4_to_slice(solve);
+        let solve_values: Vec<[i32; 4]> =
+            solves.iter().map(|&solve| i32x4_to_slice(solve)).collect();
+
+        let mut best_single = [i32::MAX; 4];
 
-            // For now, don't include entered times in single histogram
-            if *entered != 0 {
+        for values in &solve_values {
+            for (best, &value) in best_single.iter_mut().zip(values) {
+                *best = (*best).min(value);
+            }
+        }
+
+        // Entered attempts are the same in every simulation, so they're added once when generating
+        // the output rather than for every simulation
+        for (i, values) in solve_values.iter().enumerate() {
+            if competitor
+                .entered_results
+                .get(i)
+                .is_some_and(|&time| time != 0)
+            {
                 continue;
             }
 
-            self.add_to_histogram(&solve_values, &mut histograms.hist_single);
+            self.add_to_histogram(values, &mut histograms.hist_remaining);
         }
 
-        let averages = self.calculate_result(solves.as_slice());
+        let result = self.calculate_result(solves.as_slice());
 
-        self.add_to_histogram(&averages, &mut histograms.hist_average);
+        self.add_to_histogram(&result, &mut histograms.hist_average);
 
-        averages
+        SimulatedRound {
+            result,
+            average: self.calculate_average(solves.as_slice()),
+            best_single,
+            solves: solve_values,
+        }
     }
 
     fn add_to_histogram(&mut self, values: &[i32], histogram: &mut Histogram) {
@@ -58,7 +90,7 @@ pub trait EventSimulation {
                 continue;
             }
 
-            histogram.add_value(value / 10);
+            histogram.add_value(value);
         }
     }
 }
@@ -75,7 +107,7 @@ impl EventSimulation for Ao5Simulation {
     ) -> Vec<v128> {
         generate_skewnorm_vec(
             AO5_SOLVE_COUNT,
-            competitor.stats.as_ref(),
+            competitor.model_stats(),
             rng,
             config,
             competitor.entered_results.as_slice(),
@@ -99,19 +131,24 @@ impl EventSimulation for Mo3Simulation {
         config: &mut RuntimeConfig,
         rng: &mut ThreadRng,
     ) -> Vec<v128> {
-        let results = generate_skewnorm_vec(
+        if self.event == Mo3Event::F333 {
+            return generate_move_count_vec(
+                MO3_SOLVE_COUNT,
+                competitor.move_count_model.as_ref(),
+                competitor.model_stats().map_or(0.0, |stats| stats.dnf_rate),
+                rng,
+                config,
+                competitor.entered_results.as_slice(),
+            );
+        }
+
+        generate_skewnorm_vec(
             MO3_SOLVE_COUNT,
-            competitor.stats.as_ref(),
+            competitor.model_stats(),
             rng,
             config,
             competitor.entered_results.as_slice(),
-        );
-
-        if self.event == Mo3Event::F333 {
-            results.into_iter().map(i32x4_truncate_down_100).collect()
-        } else {
-            results
-        }
+        )
     }
 
     fn calculate_result(&self, solves: &[v128]) -> [i32; 4] {
@@ -131,7 +168,7 @@ impl EventSimulation for Bo3Simulation {
     ) -> Vec<v128> {
         generate_skewnorm_vec(
             BO3_SOLVE_COUNT,
-            competitor.stats.as_ref(),
+            competitor.model_stats(),
             rng,
             config,
             competitor.entered_results.as_slice(),
@@ -141,4 +178,8 @@ impl EventSimulation for Bo3Simulation {
     fn calculate_result(&self, solves: &[v128]) -> [i32; 4] {
         calc_wca_best_3(solves[0], solves[1], solves[2])
     }
+
+    fn calculate_average(&self, solves: &[v128]) -> [i32; 4] {
+        calc_wca_mean_3(solves[0], solves[1], solves[2])
+    }
 }
@@ -0,0 +1,132 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug)]
+pub struct GroupOutcomeWASMOutput {
+    name: String,
+    num_persons: u32,
+    expected_golds: f32,
+    expected_silvers: f32,
+    expected_bronzes: f32,
+    expected_medals: f32,
+    // Probability of topping the medal table (golds, then silvers, then bronzes), including ties
+    top_of_table_probability: f32,
+    // gold_count_dist[n] is the number of simulations where the group won exactly n golds
+    gold_count_dist: Vec<u32>,
+    medal_count_dist: Vec<u32>,
+}
+
+#[derive(Clone, Default)]
+struct GroupCounts {
+    medals: [u64; 3],
+    top_of_table: u32,
+    gold_count_dist: Vec<u32>,
+    medal_count_dist: Vec<u32>,
+}
+
+// Per-simulation medal tables of groups of persons, e.g. countries or teams
+pub struct GroupMedalTable {
+    names: Vec<String>,
+    // Index into names for each person, None for persons without a group
+    person_groups: Vec<Option<usize>>,
+    counts: Vec<GroupCounts>,
+}
+
+impl GroupMedalTable {
+    pub fn new(person_groups: &[Option<String>]) -> Self {
+        let mut names: Vec<String> = Vec::new();
+
+        let person_groups = person_groups
+            .iter()
+            .map(|group| {
+                let group = group.as_ref()?;
+
+                Some(
+                    names
+                        .iter()
+                        .position(|name| name == group)
+                        .unwrap_or_else(|| {
+                            names.push(group.clone());
+                            names.len() - 1
+                        }),
+                )
+            })
+            .collect();
+
+        Self {
+            counts: vec![GroupCounts::default(); names.len()],
+            names,
+            person_groups,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.counts = vec![GroupCounts::default(); self.names.len()];
+    }
+
+    // Adds one simulation, given how many of each medal every person won in it
+    pub fn add_simulation(&mut self, person_medals: &[[u32; 3]]) {
+        let mut group_medals = vec![[0; 3]; self.names.len()];
+
+        for (medals, group) in person_medals.iter().zip(&self.person_groups) {
+            if let Some(group) = *group {
+                for (total, count) in group_medals[group].iter_mut().zip(medals) {
+                    *total += count;
+                }
+            }
+        }
+
+        let best = group_medals.iter().max().copied();
+
+        for (counts, medals) in self.counts.iter_mut().zip(&group_medals) {
+            for (total, &count) in counts.medals.iter_mut().zip(medals) {
+                *total += count as u64;
+            }
+
+            if Some(*medals) == best {
+                counts.top_of_table += 1;
+            }
+
+            add_to_dist(&mut counts.gold_count_dist, medals[0]);
+            add_to_dist(&mut counts.medal_count_dist, medals.iter().sum());
+        }
+    }
+
+    pub fn generate_wasm_output(&self, num_simulations: u32) -> Vec<GroupOutcomeWASMOutput> {
+        let trials = num_simulations.max(1) as f32;
+
+        self.names
+            .iter()
+            .zip(&self.counts)
+            .enumerate()
+            .map(|(group, (name, counts))| {
+                let [golds, silvers, bronzes] = counts.medals.map(|count| count as f32 / trials);
+
+                GroupOutcomeWASMOutput {
+                    name: name.clone(),
+                    num_persons: self
+                        .person_groups
+                        .iter()
+                        .filter(|&&person_group| person_group == Some(group))
+                        .count() as u32,
+                    expected_golds: golds,
+                    expected_silvers: silvers,
+                    expected_bronzes: bronzes,
+                    expected_medals: golds + silvers + bronzes,
+                    top_of_table_probability: counts.top_of_table as f32 / trials,
+                    gold_count_dist: counts.gold_count_dist.clone(),
+                    medal_count_dist: counts.medal_count_dist.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn add_to_dist(dist: &mut Vec<u32>, value: u32) {
+    let index = value as usize;
+
+    if dist.len() <= index {
+        dist.resize(index + 1, 0);
+    }
+
+    dist[index] += 1;
+}
@@ -0,0 +1,118 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+// Space-Saving algorithm: approximately counts the most frequent items of a stream using a fixed
+// number of counters. Any item seen more than (stream length / capacity) times is guaranteed to
+// be tracked, and each count overestimates the true count by at most its error.
+#[derive(Debug, Clone)]
+pub struct SpaceSaving<K> {
+    capacity: usize,
+    counters: Vec<Counter<K>>,
+    // Index of each tracked item's counter
+    slots: HashMap<K, usize>,
+    // (count, counter index) pairs, smallest count first. Incrementing a counter pushes a new
+    // pair rather than updating the old one, so pairs whose count no longer matches their counter
+    // are stale and skipped when popped.
+    min_heap: BinaryHeap<Reverse<(u32, usize)>>,
+}
+
+#[derive(Debug, Clone)]
+struct Counter<K> {
+    item: K,
+    count: u32,
+    error: u32,
+}
+
+pub struct HeavyHitter<K> {
+    pub item: K,
+    pub count: u32,
+    pub error: u32,
+}
+
+impl<K: Hash + Eq + Clone> SpaceSaving<K> {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+
+        Self {
+            capacity,
+            counters: Vec::with_capacity(capacity),
+            slots: HashMap::with_capacity(capacity),
+            min_heap: BinaryHeap::with_capacity(capacity * 2),
+        }
+    }
+
+    pub fn add(&mut self, item: K) {
+        if let Some(&slot) = self.slots.get(&item) {
+            self.counters[slot].count += 1;
+            self.push_counter(slot);
+            return;
+        }
+
+        if self.counters.len() < self.capacity {
+            self.slots.insert(item.clone(), self.counters.len());
+            self.counters.push(Counter {
+                item,
+                count: 1,
+                error: 0,
+            });
+            self.push_counter(self.counters.len() - 1);
+            return;
+        }
+
+        // Replace the least frequent item, inheriting its count as the error bound
+        let (min_count, slot) = loop {
+            let Reverse((count, slot)) = self
+                .min_heap
+                .pop()
+                .expect("Every counter has a pair in the heap");
+
+            if self.counters[slot].count == count {
+                break (count, slot);
+            }
+        };
+
+        self.slots.remove(&self.counters[slot].item);
+        self.slots.insert(item.clone(), slot);
+        self.counters[slot] = Counter {
+            item,
+            count: min_count + 1,
+            error: min_count,
+        };
+        self.push_counter(slot);
+    }
+
+    fn push_counter(&mut self, slot: usize) {
+        self.min_heap
+            .push(Reverse((self.counters[slot].count, slot)));
+
+        // Rebuild from the current counts once stale pairs outnumber live ones, so the heap stays
+        // within a constant factor of the capacity
+        if self.min_heap.len() > self.capacity * 2 {
+            self.min_heap = self
+                .counters
+                .iter()
+                .enumerate()
+                .map(|(slot, counter)| Reverse((counter.count, slot)))
+                .collect();
+        }
+    }
+
+    // The most frequent items, most frequent first
+    pub fn top(&self, num_items: usize) -> Vec<HeavyHitter<K>> {
+        let mut items: Vec<HeavyHitter<K>> = self
+            .counters
+            .iter()
+            .map(|counter| HeavyHitter {
+                item: counter.item.clone(),
+                count: counter.count,
+                error: counter.error,
+            })
+            .collect();
+
+        items.sort_unstable_by(|a, b| b.count.cmp(&a.count).then(a.error.cmp(&b.error)));
+        items.truncate(num_items);
+
+        items
+    }
+}
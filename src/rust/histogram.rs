@@ -1,32 +1,141 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug)]
-pub struct Histogram {
-    min: i32,
-    max: i32,
+// Keeps dense histograms small for slow events and very narrow bins
+const MAX_BINS: i32 = 10_000;
+
+#[derive(Deserialize, Debug, Copy, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BinWidth {
+    // Width in the same units as results
+    Fixed { width: i32 },
+    // Chosen per competitor from the interquartile range of their fitted single distribution
+    FreedmanDiaconis,
+}
+
+pub const DEFAULT_BIN_WIDTH: i32 = 10;
+
+impl Default for BinWidth {
+    fn default() -> Self {
+        BinWidth::Fixed {
+            width: DEFAULT_BIN_WIDTH,
+        }
+    }
+}
 
-    times: HashMap<i32, i32>,
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct HistogramOptions {
+    pub bin_width: BinWidth,
+}
+
+// Freedman–Diaconis rule: 2 * IQR / cbrt(n)
+pub fn calc_freedman_diaconis_width(interquartile_range: f32, count: u32) -> i32 {
+    let width = 2.0 * interquartile_range / (count.max(1) as f32).cbrt();
+
+    if width.is_finite() {
+        (width.round() as i32).max(1)
+    } else {
+        1
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Histogram {
+    // Lower edge of the first bin
+    start: i32,
+    bin_width: i32,
+    bins: Vec<u32>,
+    // Counts of values below the first bin and above the last bin
+    underflow: u32,
+    overflow: u32,
 }
 
 impl Histogram {
-    pub fn new_with_bounds(min: i32, max: i32) -> Self {
+    pub fn new(min: i32, max: i32, bin_width: i32) -> Self {
+        if max < min {
+            return Self {
+                start: 0,
+                bin_width: bin_width.max(1),
+                bins: vec![],
+                underflow: 0,
+                overflow: 0,
+            };
+        }
+
+        let bin_width = bin_width.max(1).max((max - min) / MAX_BINS + 1);
+        let start = min.div_euclid(bin_width) * bin_width;
+        let num_bins = ((max - start) / bin_width + 1) as usize;
+
         Self {
-            min,
-            max,
-            times: HashMap::new(),
+            start,
+            bin_width,
+            bins: vec![0; num_bins],
+            underflow: 0,
+            overflow: 0,
         }
     }
 
+    // Histogram with every count in a single bin, e.g. for an attempt that's already been done
+    pub fn point_mass(value: i32, count: u32) -> Self {
+        let mut histogram = Self::new(value, value, 1);
+        histogram.add_count(value, count);
+
+        histogram
+    }
+
     pub fn add_value(&mut self, value: i32) {
-        if value < self.min || value > self.max {
+        self.add_count(value, 1);
+    }
+
+    fn add_count(&mut self, value: i32, count: u32) {
+        if value < self.start {
+            self.underflow += count;
             return;
         }
 
-        *self.times.entry(value).or_insert(0) += 1;
+        match self
+            .bins
+            .get_mut(((value - self.start) / self.bin_width) as usize)
+        {
+            Some(bin) => *bin += count,
+            None => self.overflow += count,
+        }
+    }
+
+    // Bins of the other histogram are added by their lower edge, so merging histograms with
+    // different layouts is only exact when the other histogram's bins are aligned with these
+    pub fn merge(&mut self, other: &Histogram) {
+        if self.start == other.start && self.bin_width == other.bin_width {
+            for (bin, &count) in self.bins.iter_mut().zip(&other.bins) {
+                *bin += count;
+            }
+
+            let extra: u32 = other.bins.iter().skip(self.bins.len()).sum();
+            self.overflow += extra;
+        } else {
+            for (i, &count) in other.bins.iter().enumerate() {
+                if count > 0 {
+                    self.add_count(other.start + i as i32 * other.bin_width, count);
+                }
+            }
+        }
+
+        self.underflow += other.underflow;
+        self.overflow += other.overflow;
     }
 
-    // Gets the data and consumes self
-    pub fn data(self) -> HashMap<i32, i32> {
-        self.times
+    // Non-empty bins keyed by their lower edge in deciseconds
+    pub fn to_sparse(&self) -> HashMap<i32, i32> {
+        let mut sparse = HashMap::new();
+
+        for (i, &count) in self.bins.iter().enumerate() {
+            if count > 0 {
+                let key = (self.start + i as i32 * self.bin_width) / 10;
+                *sparse.entry(key).or_insert(0) += count as i32;
+            }
+        }
+
+        sparse
     }
 }
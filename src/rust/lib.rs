@@ -1,19 +1,48 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use wasm_bindgen::prelude::{wasm_bindgen, JsValue};
 use web_sys::js_sys::Promise;
 
+use backtest::{run_backtest, BacktestCase, BacktestOptions};
+use competitor::{ModelOptions, StatsOverride};
 use data::CompetitionDataManager;
 use event::EventType;
-use simulation::{CompetitionSimulator, RuntimeConfig};
+use histogram::HistogramOptions;
+use multi_event::{CompetitionEventInput, MultiEventSimulator};
+use penalty::EnteredAttempt;
+use records::RecordInput;
+use robustness::{run_robustness_analysis, RobustnessOptions};
+use simulation::{CompetitionSimulator, ConvergenceTarget, RuntimeConfig, TrackingOptions};
+use targets::calc_target_results;
+use tuning::{tune_halflife, TuningOptions};
+use unofficial::{parse_unofficial_results, UnofficialFormat, UnofficialOptions};
+use wcif::parse_wcif;
 
+mod backtest;
 mod calc;
+mod calibration;
 mod competitor;
 mod data;
+mod diagnostics;
 mod event;
 mod event_simulator;
+mod groups;
+mod heavy_hitters;
 mod histogram;
+mod move_count;
+mod multi_event;
+mod overall;
+mod penalty;
+mod prior;
+mod records;
+mod robustness;
 mod simd;
 mod simulation;
+mod summary;
+mod targets;
+mod tuning;
+mod unofficial;
+mod wcif;
 
 #[macro_export]
 #[allow(unused_macros)]
@@ -29,15 +58,25 @@ thread_local! {
 
 pub struct AppState {
     simulation_manager: RefCell<Option<CompetitionSimulator>>,
+    competition_manager: RefCell<Option<MultiEventSimulator>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         Self {
             simulation_manager: RefCell::new(None),
+            competition_manager: RefCell::new(None),
         }
     }
 
+    pub fn set_competition_manager(&self, simulator: MultiEventSimulator) {
+        *self.competition_manager.borrow_mut() = Some(simulator);
+    }
+
+    pub fn get_competition_manager(&self) -> &RefCell<Option<MultiEventSimulator>> {
+        &self.competition_manager
+    }
+
     pub fn set_simulation_manager(&self, simulator: CompetitionSimulator) {
         *self.simulation_manager.borrow_mut() = Some(simulator);
     }
@@ -72,20 +111,117 @@ pub fn load_data(
     start_date: i64,
     end_date: i64,
     halflife: f32,
+    model_options_jsval: JsValue,
+) -> Promise {
+    let event_type = match EventType::from_event_id(&event_str) {
+        Some(event) => event,
+        None => return str_to_jsval("Invalid event type.").into(),
+    };
+
+    let model_options: ModelOptions =
+        match serde_wasm_bindgen::from_value::<Option<ModelOptions>>(model_options_jsval) {
+            Ok(options) => options.unwrap_or_default(),
+            Err(_) => return str_to_jsval("Invalid model options.").into(),
+        };
+
+    load_competitors(
+        competitors,
+        event_type,
+        start_date,
+        end_date,
+        halflife,
+        model_options,
+    )
+}
+
+#[wasm_bindgen]
+pub fn import_wcif(contents: String) -> JsValue {
+    match parse_wcif(&contents) {
+        Ok(competition) => serde_wasm_bindgen::to_value(&competition).unwrap(),
+        Err(e) => str_to_jsval(e),
+    }
+}
+
+// Loads the registered competitors of one event in a WCIF, returning the event's setup. Only the
+// competitor list is applied to the simulation; round formats, cutoffs and time limits aren't.
+#[wasm_bindgen]
+pub fn load_wcif_event(
+    contents: String,
+    event_str: String,
+    start_date: i64,
+    end_date: i64,
+    halflife: f32,
+    model_options_jsval: JsValue,
 ) -> Promise {
     let event_type = match EventType::from_event_id(&event_str) {
         Some(event) => event,
         None => return str_to_jsval("Invalid event type.").into(),
     };
 
-    let data_manager =
-        CompetitionDataManager::create(competitors, event_type, start_date, end_date, halflife);
+    let model_options: ModelOptions =
+        match serde_wasm_bindgen::from_value::<Option<ModelOptions>>(model_options_jsval) {
+            Ok(options) => options.unwrap_or_default(),
+            Err(_) => return str_to_jsval("Invalid model options.").into(),
+        };
+
+    let competition = match parse_wcif(&contents) {
+        Ok(competition) => competition,
+        Err(e) => return str_to_jsval(e).into(),
+    };
+
+    let Some(event_setup) = competition
+        .events
+        .into_iter()
+        .find(|event| event.event_id == event_str)
+    else {
+        return str_to_jsval("Event not held at this competition.").into();
+    };
+
+    let competitors = event_setup.competitors.clone();
+    let setup_jsval = serde_wasm_bindgen::to_value(&event_setup).unwrap();
+
+    let loaded = load_competitors(
+        competitors,
+        event_type,
+        start_date,
+        end_date,
+        halflife,
+        model_options,
+    );
+
+    wasm_bindgen_futures::future_to_promise(async move {
+        let loaded = wasm_bindgen_futures::JsFuture::from(loaded).await?;
+
+        if loaded.as_bool() == Some(true) {
+            Ok(setup_jsval)
+        } else {
+            Ok(loaded)
+        }
+    })
+}
+
+fn load_competitors(
+    competitors: Vec<String>,
+    event_type: EventType,
+    start_date: i64,
+    end_date: i64,
+    halflife: f32,
+    model_options: ModelOptions,
+) -> Promise {
+    let data_manager = CompetitionDataManager::create(
+        competitors,
+        vec![event_type],
+        start_date,
+        end_date,
+        halflife,
+        model_options,
+    );
 
     let future = async move {
         let competitors_result = data_manager.fetch_all().await;
 
         let competitors = match competitors_result {
-            Ok(fetch_data) => fetch_data,
+            Ok(mut fetch_data) => fetch_data.remove(&event_type).unwrap_or_default(),
             Err(e) => return Ok(serde_wasm_bindgen::to_value(&format!("Error: {e:?}")).unwrap()),
         };
 
@@ -107,7 +243,7 @@ pub fn run_simulation(
     include_dnf: bool,
     entered_times_jsval: JsValue,
 ) -> JsValue {
-    let entered_times: Vec<Vec<i32>> =
+    let entered_times: Vec<Vec<EnteredAttempt>> =
         serde_wasm_bindgen::from_value(entered_times_jsval).expect("Invalid input");
 
     APP_STATE.with(|state| {
@@ -122,6 +258,7 @@ pub fn run_simulation(
         let mut config = RuntimeConfig {
             include_dnf,
             num_simulations,
+            convergence: None,
         };
 
         sim_manager.run_simulations(&mut config);
@@ -131,3 +268,596 @@ pub fn run_simulation(
         serde_wasm_bindgen::to_value(&simulated_data).unwrap()
     })
 }
+
+// Loads every event of a competition, fetching each person's results once
+#[wasm_bindgen]
+pub fn load_competition(
+    events_jsval: JsValue,
+    start_date: i64,
+    end_date: i64,
+    halflife: f32,
+    model_options_jsval: JsValue,
+) -> Promise {
+    let event_inputs: Vec<CompetitionEventInput> =
+        match serde_wasm_bindgen::from_value(events_jsval) {
+            Ok(events) => events,
+            Err(_) => return str_to_jsval("Invalid competition events.").into(),
+        };
+
+    let model_options: ModelOptions =
+        match serde_wasm_bindgen::from_value::<Option<ModelOptions>>(model_options_jsval) {
+            Ok(options) => options.unwrap_or_default(),
+            Err(_) => return str_to_jsval("Invalid model options.").into(),
+        };
+
+    load_competition_events(event_inputs, start_date, end_date, halflife, model_options)
+}
+
+// Loads every supported event of a WCIF with its registered competitors, returning the parsed
+// competition. Like load_wcif_event, rounds are simulated in the event's default format: cutoffs
+// and time limits aren't applied, so attempts that would be cut off or over the limit are still
+// simulated as completed.
+#[wasm_bindgen]
+pub fn load_wcif_competition(
+    contents: String,
+    start_date: i64,
+    end_date: i64,
+    halflife: f32,
+    model_options_jsval: JsValue,
+) -> Promise {
+    let model_options: ModelOptions =
+        match serde_wasm_bindgen::from_value::<Option<ModelOptions>>(model_options_jsval) {
+            Ok(options) => options.unwrap_or_default(),
+            Err(_) => return str_to_jsval("Invalid model options.").into(),
+        };
+
+    let competition = match parse_wcif(&contents) {
+        Ok(competition) => competition,
+        Err(e) => return str_to_jsval(e).into(),
+    };
+
+    let competition_jsval = serde_wasm_bindgen::to_value(&competition).unwrap();
+
+    let event_inputs: Vec<CompetitionEventInput> = competition
+        .events
+        .into_iter()
+        .filter(|event| event.supported && !event.competitors.is_empty())
+        .map(|event| CompetitionEventInput {
+            event_id: event.event_id,
+            competitors: event.competitors,
+        })
+        .collect();
+
+    if event_inputs.is_empty() {
+        return str_to_jsval("No supported events with registered competitors.").into();
+    }
+
+    let loaded =
+        load_competition_events(event_inputs, start_date, end_date, halflife, model_options);
+
+    wasm_bindgen_futures::future_to_promise(async move {
+        let loaded = wasm_bindgen_futures::JsFuture::from(loaded).await?;
+
+        if loaded.as_bool() == Some(true) {
+            Ok(competition_jsval)
+        } else {
+            Ok(loaded)
+        }
+    })
+}
+
+fn load_competition_events(
+    event_inputs: Vec<CompetitionEventInput>,
+    start_date: i64,
+    end_date: i64,
+    halflife: f32,
+    model_options: ModelOptions,
+) -> Promise {
+    let mut events = Vec::with_capacity(event_inputs.len());
+    let mut wca_ids: Vec<String> = Vec::new();
+
+    for input in &event_inputs {
+        match EventType::from_event_id(&input.event_id) {
+            Some(event) => events.push(event),
+            None => return str_to_jsval("Invalid event type.").into(),
+        }
+
+        for wca_id in &input.competitors {
+            if !wca_ids.contains(wca_id) {
+                wca_ids.push(wca_id.clone());
+            }
+        }
+    }
+
+    let data_manager = CompetitionDataManager::create(
+        wca_ids.clone(),
+        events,
+        start_date,
+        end_date,
+        halflife,
+        model_options,
+    );
+
+    let future = async move {
+        let competitors_by_event = match data_manager.fetch_all().await {
+            Ok(fetch_data) => fetch_data,
+            Err(e) => return Ok(serde_wasm_bindgen::to_value(&format!("Error: {e:?}")).unwrap()),
+        };
+
+        let simulator =
+            match MultiEventSimulator::new(&wca_ids, &event_inputs, competitors_by_event) {
+                Ok(simulator) => simulator,
+                Err(e) => return Ok(str_to_jsval(e)),
+            };
+
+        APP_STATE.with(|state| state.set_competition_manager(simulator));
+
+        serde_wasm_bindgen::to_value(&true)
+            .map_err(|_| str_to_jsval("Error serializing return value"))
+    };
+    wasm_bindgen_futures::future_to_promise(future)
+}
+
+#[wasm_bindgen]
+pub fn run_competition_simulation(num_simulations: u32, include_dnf: bool) -> JsValue {
+    APP_STATE.with(|state| {
+        let mut competition_ref = state.get_competition_manager().borrow_mut();
+        let competition = match competition_ref.as_mut() {
+            Some(data) => data,
+            None => return str_to_jsval("Competition data not loaded"),
+        };
+
+        let mut config = RuntimeConfig {
+            include_dnf,
+            num_simulations,
+            convergence: None,
+        };
+
+        competition.run_simulations(&mut config);
+
+        serde_wasm_bindgen::to_value(&competition.generate_wasm_output()).unwrap()
+    })
+}
+
+// Labels are keyed by WCA id. Persons without a label are grouped by country.
+#[wasm_bindgen]
+pub fn set_competition_groups(labels_jsval: JsValue) -> JsValue {
+    let labels: HashMap<String, String> =
+        match serde_wasm_bindgen::from_value::<Option<HashMap<String, String>>>(labels_jsval) {
+            Ok(labels) => labels.unwrap_or_default(),
+            Err(_) => return str_to_jsval("Invalid group labels"),
+        };
+
+    APP_STATE.with(|state| {
+        let mut competition_ref = state.get_competition_manager().borrow_mut();
+        let competition = match competition_ref.as_mut() {
+            Some(data) => data,
+            None => return str_to_jsval("Competition data not loaded"),
+        };
+
+        competition.set_groups(&labels);
+
+        JsValue::TRUE
+    })
+}
+
+// Predicts past finals from the results before each competition and scores the predictions
+#[wasm_bindgen]
+pub fn backtest(cases_jsval: JsValue, options_jsval: JsValue) -> Promise {
+    let cases: Vec<BacktestCase> = match serde_wasm_bindgen::from_value(cases_jsval) {
+        Ok(cases) => cases,
+        Err(_) => return str_to_jsval("Invalid backtest cases.").into(),
+    };
+
+    let options: BacktestOptions =
+        match serde_wasm_bindgen::from_value::<Option<BacktestOptions>>(options_jsval) {
+            Ok(options) => options.unwrap_or_default(),
+            Err(_) => return str_to_jsval("Invalid backtest options.").into(),
+        };
+
+    wasm_bindgen_futures::future_to_promise(async move {
+        let report = run_backtest(&cases, &options).await;
+
+        serde_wasm_bindgen::to_value(&report)
+            .map_err(|_| str_to_jsval("Error serializing return value"))
+    })
+}
+
+// Fetches the competitors' results once, and finds the half-life that best predicts their most
+// recent competitions
+#[wasm_bindgen]
+pub fn recommend_halflife(
+    competitors: Vec<String>,
+    event_str: String,
+    start_date: i64,
+    end_date: i64,
+    options_jsval: JsValue,
+) -> Promise {
+    let event_type = match EventType::from_event_id(&event_str) {
+        Some(event) => event,
+        None => return str_to_jsval("Invalid event type.").into(),
+    };
+
+    let options: TuningOptions =
+        match serde_wasm_bindgen::from_value::<Option<TuningOptions>>(options_jsval) {
+            Ok(options) => options.unwrap_or_default(),
+            Err(_) => return str_to_jsval("Invalid tuning options.").into(),
+        };
+
+    let data_manager = CompetitionDataManager::create(
+        competitors,
+        vec![event_type],
+        start_date,
+        end_date,
+        options.halflives.first().copied().unwrap_or(180.0),
+        options.model_options.clone(),
+    );
+
+    wasm_bindgen_futures::future_to_promise(async move {
+        let competitors = match data_manager.fetch_all().await {
+            Ok(mut fetch_data) => fetch_data.remove(&event_type).unwrap_or_default(),
+            Err(e) => return Ok(str_to_jsval(e)),
+        };
+
+        serde_wasm_bindgen::to_value(&tune_halflife(&competitors, &options))
+            .map_err(|_| str_to_jsval("Error serializing return value"))
+    })
+}
+
+// Shows how much each competitor's win probability moves across a grid of model settings
+#[wasm_bindgen]
+pub fn run_robustness_simulation(options_jsval: JsValue) -> JsValue {
+    let options: RobustnessOptions =
+        match serde_wasm_bindgen::from_value::<Option<RobustnessOptions>>(options_jsval) {
+            Ok(options) => options.unwrap_or_default(),
+            Err(_) => return str_to_jsval("Invalid robustness options."),
+        };
+
+    APP_STATE.with(|state| {
+        state.with(|sim_manager| match sim_manager {
+            Some(sim_manager) => match run_robustness_analysis(sim_manager, &options) {
+                Ok(output) => serde_wasm_bindgen::to_value(&output)
+                    .unwrap_or_else(|_| str_to_jsval("Error serializing robustness output")),
+                Err(e) => str_to_jsval(e),
+            },
+            None => str_to_jsval("Simulation data not loaded"),
+        })
+    })
+}
+
+#[wasm_bindgen]
+pub fn get_fit_diagnostics() -> JsValue {
+    APP_STATE.with(|state| {
+        state.with(|sim_manager| match sim_manager {
+            Some(sim_manager) => serde_wasm_bindgen::to_value(&sim_manager.generate_fit_output())
+                .unwrap_or_else(|_| str_to_jsval("Error serializing fit diagnostics")),
+            None => str_to_jsval("Simulation data not loaded"),
+        })
+    })
+}
+
+#[wasm_bindgen]
+pub fn get_competitor_models() -> JsValue {
+    APP_STATE.with(|state| {
+        state.with(|sim_manager| match sim_manager {
+            Some(sim_manager) => serde_wasm_bindgen::to_value(&sim_manager.generate_model_output())
+                .unwrap_or_else(|_| str_to_jsval("Error serializing competitor models")),
+            None => str_to_jsval("Simulation data not loaded"),
+        })
+    })
+}
+
+#[wasm_bindgen]
+pub fn set_model_override(competitor_index: usize, override_jsval: JsValue) -> JsValue {
+    let stats_override: Option<StatsOverride> = match serde_wasm_bindgen::from_value(override_jsval)
+    {
+        Ok(stats_override) => stats_override,
+        Err(_) => return str_to_jsval("Invalid model override"),
+    };
+
+    APP_STATE.with(|state| {
+        let mut sim_manager_ref = state.get_simulation_manager().borrow_mut();
+        let sim_manager = match sim_manager_ref.as_mut() {
+            Some(data) => data,
+            None => return str_to_jsval("Simulation data not loaded"),
+        };
+
+        match sim_manager.set_stats_override(competitor_index, stats_override) {
+            Ok(()) => JsValue::TRUE,
+            Err(e) => str_to_jsval(e),
+        }
+    })
+}
+
+#[wasm_bindgen]
+pub fn import_unofficial_results(
+    competitor_index: usize,
+    contents: String,
+    format_str: String,
+    start_date: i64,
+    end_date: i64,
+    options_jsval: JsValue,
+) -> JsValue {
+    let format = match UnofficialFormat::from_format_id(&format_str) {
+        Some(format) => format,
+        None => return str_to_jsval("Invalid unofficial result format"),
+    };
+
+    let options: UnofficialOptions =
+        match serde_wasm_bindgen::from_value::<Option<UnofficialOptions>>(options_jsval) {
+            Ok(options) => options.unwrap_or_default(),
+            Err(_) => return str_to_jsval("Invalid unofficial result options"),
+        };
+
+    let unofficial_results =
+        match parse_unofficial_results(&contents, format, &options, start_date, end_date) {
+            Ok(results) => results,
+            Err(e) => return str_to_jsval(e),
+        };
+
+    let output = unofficial_results.generate_import_output();
+
+    APP_STATE.with(|state| {
+        let mut sim_manager_ref = state.get_simulation_manager().borrow_mut();
+        let sim_manager = match sim_manager_ref.as_mut() {
+            Some(data) => data,
+            None => return str_to_jsval("Simulation data not loaded"),
+        };
+
+        match sim_manager.set_unofficial_results(competitor_index, Some(unofficial_results)) {
+            Ok(()) => serde_wasm_bindgen::to_value(&output)
+                .unwrap_or_else(|_| str_to_jsval("Error serializing return value")),
+            Err(e) => str_to_jsval(e),
+        }
+    })
+}
+
+#[wasm_bindgen]
+pub fn clear_unofficial_results(competitor_index: usize) -> JsValue {
+    APP_STATE.with(|state| {
+        let mut sim_manager_ref = state.get_simulation_manager().borrow_mut();
+        let sim_manager = match sim_manager_ref.as_mut() {
+            Some(data) => data,
+            None => return str_to_jsval("Simulation data not loaded"),
+        };
+
+        match sim_manager.set_unofficial_results(competitor_index, None) {
+            Ok(()) => JsValue::TRUE,
+            Err(e) => str_to_jsval(e),
+        }
+    })
+}
+
+#[wasm_bindgen]
+pub fn run_adaptive_simulation(
+    target_half_width: f32,
+    max_simulations: u32,
+    batch_size: u32,
+    include_dnf: bool,
+    entered_times_jsval: JsValue,
+) -> JsValue {
+    let entered_times: Vec<Vec<EnteredAttempt>> =
+        match serde_wasm_bindgen::from_value(entered_times_jsval) {
+            Ok(entered_times) => entered_times,
+            Err(_) => return str_to_jsval("Invalid entered times"),
+        };
+
+    APP_STATE.with(|state| {
+        let mut sim_manager_ref = state.get_simulation_manager().borrow_mut();
+        let sim_manager = match sim_manager_ref.as_mut() {
+            Some(data) => data,
+            None => return str_to_jsval("Simulation data not loaded"),
+        };
+
+        sim_manager.set_entered_results(entered_times);
+
+        let mut config = RuntimeConfig {
+            include_dnf,
+            num_simulations: max_simulations,
+            convergence: Some(ConvergenceTarget {
+                half_width: target_half_width,
+                batch_size,
+            }),
+        };
+
+        sim_manager.run_simulations(&mut config);
+
+        let simulated_data = sim_manager.generate_wasm_output();
+
+        serde_wasm_bindgen::to_value(&simulated_data).unwrap()
+    })
+}
+
+#[wasm_bindgen]
+pub fn start_simulation(
+    include_dnf: bool,
+    entered_times_jsval: JsValue,
+    expected_simulations: Option<u32>,
+) -> JsValue {
+    let entered_times: Vec<Vec<EnteredAttempt>> =
+        match serde_wasm_bindgen::from_value(entered_times_jsval) {
+            Ok(entered_times) => entered_times,
+            Err(_) => return str_to_jsval("Invalid entered times"),
+        };
+
+    APP_STATE.with(|state| {
+        let mut sim_manager_ref = state.get_simulation_manager().borrow_mut();
+        let sim_manager = match sim_manager_ref.as_mut() {
+            Some(data) => data,
+            None => return str_to_jsval("Simulation data not loaded"),
+        };
+
+        sim_manager.set_entered_results(entered_times);
+
+        // Stepping runs until cancelled, so the expected count is only used to size histogram bins
+        sim_manager.start_simulations(RuntimeConfig {
+            include_dnf,
+            num_simulations: expected_simulations.unwrap_or(0),
+            convergence: None,
+        });
+
+        JsValue::TRUE
+    })
+}
+
+#[wasm_bindgen]
+pub fn step_simulation(num_simulations: u32) -> JsValue {
+    APP_STATE.with(|state| {
+        let mut sim_manager_ref = state.get_simulation_manager().borrow_mut();
+        let sim_manager = match sim_manager_ref.as_mut() {
+            Some(data) => data,
+            None => return str_to_jsval("Simulation data not loaded"),
+        };
+
+        if let Err(e) = sim_manager.step_simulations(num_simulations) {
+            return str_to_jsval(e);
+        }
+
+        let simulated_data = sim_manager.generate_wasm_snapshot();
+
+        serde_wasm_bindgen::to_value(&simulated_data).unwrap()
+    })
+}
+
+#[wasm_bindgen]
+pub fn cancel_simulation() {
+    APP_STATE.with(|state| {
+        if let Some(sim_manager) = state.get_simulation_manager().borrow_mut().as_mut() {
+            sim_manager.cancel_simulations();
+        }
+    })
+}
+
+#[wasm_bindgen]
+pub fn get_target_results(
+    num_scenarios: u32,
+    include_dnf: bool,
+    advancing: Option<u32>,
+    entered_times_jsval: JsValue,
+) -> JsValue {
+    let entered_times: Vec<Vec<EnteredAttempt>> =
+        match serde_wasm_bindgen::from_value(entered_times_jsval) {
+            Ok(entered_times) => entered_times,
+            Err(_) => return str_to_jsval("Invalid entered times"),
+        };
+
+    APP_STATE.with(|state| {
+        let mut sim_manager_ref = state.get_simulation_manager().borrow_mut();
+        let sim_manager = match sim_manager_ref.as_mut() {
+            Some(data) => data,
+            None => return str_to_jsval("Simulation data not loaded"),
+        };
+
+        sim_manager.set_entered_results(entered_times);
+
+        let mut config = RuntimeConfig {
+            include_dnf,
+            num_simulations: num_scenarios,
+            convergence: None,
+        };
+
+        let targets = calc_target_results(
+            sim_manager,
+            &mut config,
+            num_scenarios,
+            advancing.map(|advancing| advancing as usize),
+        );
+
+        serde_wasm_bindgen::to_value(&targets).unwrap()
+    })
+}
+
+#[wasm_bindgen]
+pub fn set_tracking_options(tracking_jsval: JsValue) -> JsValue {
+    let tracking: TrackingOptions =
+        match serde_wasm_bindgen::from_value::<Option<TrackingOptions>>(tracking_jsval) {
+            Ok(tracking) => tracking.unwrap_or_default(),
+            Err(_) => return str_to_jsval("Invalid tracking options"),
+        };
+
+    APP_STATE.with(|state| {
+        let mut sim_manager_ref = state.get_simulation_manager().borrow_mut();
+        let sim_manager = match sim_manager_ref.as_mut() {
+            Some(data) => data,
+            None => return str_to_jsval("Simulation data not loaded"),
+        };
+
+        sim_manager.set_tracking_options(tracking);
+
+        JsValue::TRUE
+    })
+}
+
+#[wasm_bindgen]
+pub fn set_records(records_jsval: JsValue) -> JsValue {
+    let records: RecordInput =
+        match serde_wasm_bindgen::from_value::<Option<RecordInput>>(records_jsval) {
+            Ok(records) => records.unwrap_or_default(),
+            Err(_) => return str_to_jsval("Invalid records"),
+        };
+
+    APP_STATE.with(|state| {
+        let mut sim_manager_ref = state.get_simulation_manager().borrow_mut();
+        let sim_manager = match sim_manager_ref.as_mut() {
+            Some(data) => data,
+            None => return str_to_jsval("Simulation data not loaded"),
+        };
+
+        sim_manager.set_records(records);
+
+        JsValue::TRUE
+    })
+}
+
+#[wasm_bindgen]
+pub fn set_histogram_options(options_jsval: JsValue) -> JsValue {
+    let options: HistogramOptions =
+        match serde_wasm_bindgen::from_value::<Option<HistogramOptions>>(options_jsval) {
+            Ok(options) => options.unwrap_or_default(),
+            Err(_) => return str_to_jsval("Invalid histogram options"),
+        };
+
+    APP_STATE.with(|state| {
+        let mut sim_manager_ref = state.get_simulation_manager().borrow_mut();
+        let sim_manager = match sim_manager_ref.as_mut() {
+            Some(data) => data,
+            None => return str_to_jsval("Simulation data not loaded"),
+        };
+
+        sim_manager.set_histogram_options(options);
+
+        JsValue::TRUE
+    })
+}
+
+#[wasm_bindgen]
+pub fn get_head_to_head() -> JsValue {
+    APP_STATE.with(|state| {
+        state.with(|sim_manager| {
+            let Some(sim_manager) = sim_manager else {
+                return str_to_jsval("Simulation data not loaded");
+            };
+
+            match sim_manager.generate_head_to_head_output() {
+                Some(head_to_head) => serde_wasm_bindgen::to_value(&head_to_head).unwrap(),
+                None => str_to_jsval("Head-to-head tracking is not enabled"),
+            }
+        })
+    })
+}
+
+#[wasm_bindgen]
+pub fn get_podium_outcomes(num_outcomes: usize) -> JsValue {
+    APP_STATE.with(|state| {
+        state.with(|sim_manager| {
+            let Some(sim_manager) = sim_manager else {
+                return str_to_jsval("Simulation data not loaded");
+            };
+
+            match sim_manager.generate_podium_outcome_output(num_outcomes) {
+                Some(outcomes) => serde_wasm_bindgen::to_value(&outcomes).unwrap(),
+                None => str_to_jsval("Podium outcome tracking is not enabled"),
+            }
+        })
+    })
+}
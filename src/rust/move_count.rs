@@ -0,0 +1,76 @@
+use rand::Rng;
+
+use crate::{calc::skewnorm_cdf, competitor::CompetitorStats};
+
+// FMC solutions are limited to 80 moves
+const MAX_MOVES: i32 = 80;
+// FMC results are stored in hundredths of a move so means can be represented exactly
+const MOVE_SCALE: i32 = 100;
+
+// Discretized skew-normal fit over whole move counts, used for FMC
+#[derive(Debug, Clone)]
+pub struct MoveCountModel {
+    cumulative: Vec<f32>,
+}
+
+impl MoveCountModel {
+    pub fn from_stats(stats: &CompetitorStats) -> Option<Self> {
+        if stats.location.is_nan() || stats.shape.is_nan() || stats.shape <= 0.0 {
+            return None;
+        }
+
+        let cdf = |moves: f32| {
+            skewnorm_cdf(
+                moves * MOVE_SCALE as f32,
+                stats.skew,
+                stats.shape,
+                stats.location,
+            )
+        };
+
+        // Probability of each move count from 1 to MAX_MOVES, with the tails folded into the ends
+        let probabilities: Vec<f32> = (1..=MAX_MOVES)
+            .map(|moves| {
+                let lower = if moves == 1 {
+                    0.0
+                } else {
+                    cdf(moves as f32 - 0.5)
+                };
+                let upper = if moves == MAX_MOVES {
+                    1.0
+                } else {
+                    cdf(moves as f32 + 0.5)
+                };
+
+                (upper - lower).max(0.0)
+            })
+            .collect();
+
+        let total: f32 = probabilities.iter().sum();
+
+        if total <= 0.0 {
+            return None;
+        }
+
+        let cumulative = probabilities
+            .iter()
+            .scan(0.0, |sum, probability| {
+                *sum += probability / total;
+                Some(*sum)
+            })
+            .collect();
+
+        Some(Self { cumulative })
+    }
+
+    // Draws a move count, in hundredths of a move
+    pub fn sample(&self, rng: &mut impl Rng) -> i32 {
+        let u: f32 = rng.random();
+        let index = self
+            .cumulative
+            .partition_point(|&cumulative| cumulative < u);
+        let moves = (index as i32 + 1).min(MAX_MOVES);
+
+        moves * MOVE_SCALE
+    }
+}
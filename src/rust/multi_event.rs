@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::competitor::Competitor;
+use crate::data::ResultsByEvent;
+use crate::event::EventType;
+use crate::groups::{GroupMedalTable, GroupOutcomeWASMOutput};
+use crate::overall::{calc_kinch_scores, calc_overall_placements, calc_sum_of_ranks, EventFinish};
+use crate::simulation::{CompetitionSimulator, RuntimeConfig, SimulationWASMOutput};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CompetitionEventInput {
+    pub event_id: String,
+    // WCA ids of the people competing in the event
+    pub competitors: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PersonOutcomeWASMOutput {
+    wca_id: String,
+    name: String,
+    num_events: u32,
+    expected_golds: f32,
+    expected_silvers: f32,
+    expected_bronzes: f32,
+    expected_medals: f32,
+    // Probability of winning at least one event, and of getting at least one podium
+    win_any_probability: f32,
+    podium_any_probability: f32,
+    expected_kinch_score: f32,
+    expected_sum_of_ranks: f32,
+    // Counts of finishing in each overall place (first place first) by each ranking
+    kinch_placement_dist: Vec<u32>,
+    sum_of_ranks_placement_dist: Vec<u32>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EventOutcomeWASMOutput {
+    event_id: String,
+    results: Vec<SimulationWASMOutput>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CompetitionWASMOutput {
+    num_simulations: u32,
+    persons: Vec<PersonOutcomeWASMOutput>,
+    groups: Vec<GroupOutcomeWASMOutput>,
+    events: Vec<EventOutcomeWASMOutput>,
+}
+
+struct Person {
+    wca_id: String,
+    name: String,
+    country: Option<String>,
+}
+
+struct EventEntry {
+    event: EventType,
+    simulator: CompetitionSimulator,
+    // Index into the competition's persons for each of the event's competitors
+    person_indices: Vec<usize>,
+}
+
+#[derive(Clone)]
+struct PersonCounts {
+    // Times finishing in each podium place, summed across events
+    medals: [u32; 3],
+    win_any: u32,
+    podium_any: u32,
+    total_kinch_score: f64,
+    total_sum_of_ranks: u64,
+    kinch_placements: Vec<u32>,
+    sum_of_ranks_placements: Vec<u32>,
+}
+
+impl PersonCounts {
+    fn new(num_persons: usize) -> Self {
+        Self {
+            medals: [0; 3],
+            win_any: 0,
+            podium_any: 0,
+            total_kinch_score: 0.0,
+            total_sum_of_ranks: 0,
+            kinch_placements: vec![0; num_persons],
+            sum_of_ranks_placements: vec![0; num_persons],
+        }
+    }
+}
+
+// Simulates every event of a competition together, so outcomes can be combined per person
+pub struct MultiEventSimulator {
+    persons: Vec<Person>,
+    events: Vec<EventEntry>,
+    person_counts: Vec<PersonCounts>,
+    groups: GroupMedalTable,
+    num_simulations_run: u32,
+}
+
+impl MultiEventSimulator {
+    // Competitors are every requested person, in the order of `wca_ids`, for each event
+    pub fn new(
+        wca_ids: &[String],
+        event_inputs: &[CompetitionEventInput],
+        mut competitors_by_event: ResultsByEvent<Competitor>,
+    ) -> Result<Self, &'static str> {
+        let mut persons: Vec<Person> = wca_ids
+            .iter()
+            .map(|wca_id| Person {
+                wca_id: wca_id.clone(),
+                name: wca_id.clone(),
+                country: None,
+            })
+            .collect();
+
+        let mut events = Vec::with_capacity(event_inputs.len());
+
+        for input in event_inputs {
+            let event = EventType::from_event_id(&input.event_id).ok_or("Invalid event type")?;
+            let mut all_competitors: Vec<Option<Competitor>> = competitors_by_event
+                .remove(&event)
+                .ok_or("Event data not loaded")?
+                .into_iter()
+                .map(Some)
+                .collect();
+
+            let mut competitors = Vec::with_capacity(input.competitors.len());
+            let mut person_indices = Vec::with_capacity(input.competitors.len());
+
+            for wca_id in &input.competitors {
+                let person_index = wca_ids
+                    .iter()
+                    .position(|id| id == wca_id)
+                    .ok_or("Competitor data not loaded")?;
+                let competitor = all_competitors[person_index]
+                    .take()
+                    .ok_or("Competitor listed twice in an event")?;
+
+                persons[person_index].name = competitor.name.clone();
+                persons[person_index].country = competitor.country.clone();
+                competitors.push(competitor);
+                person_indices.push(person_index);
+            }
+
+            if competitors.is_empty() {
+                continue;
+            }
+
+            events.push(EventEntry {
+                event,
+                simulator: CompetitionSimulator::new(event, competitors),
+                person_indices,
+            });
+        }
+
+        let countries: Vec<Option<String>> = persons
+            .iter()
+            .map(|person| person.country.clone())
+            .collect();
+
+        Ok(Self {
+            person_counts: vec![PersonCounts::new(persons.len()); persons.len()],
+            groups: GroupMedalTable::new(&countries),
+            persons,
+            events,
+            num_simulations_run: 0,
+        })
+    }
+
+    pub fn run_simulations(&mut self, config: &mut RuntimeConfig) {
+        let num_persons = self.persons.len();
+
+        self.person_counts = vec![PersonCounts::new(num_persons); num_persons];
+        self.groups.reset();
+        self.num_simulations_run = 0;
+
+        for entry in &mut self.events {
+            entry.simulator.reset_results(config.num_simulations);
+        }
+
+        for _ in 0..config.num_simulations / 4 {
+            // Placements of each event, for the same 4 simulations
+            let placements: Vec<_> = self
+                .events
+                .iter_mut()
+                .map(|entry| entry.simulator.simulate_placements(config))
+                .collect();
+
+            for lane in 0..4 {
+                let mut person_medals = vec![[0; 3]; num_persons];
+
+                for (entry, event_placements) in self.events.iter().zip(&placements) {
+                    let order = &event_placements[lane].order;
+
+                    for (place, &competitor_index) in order.iter().take(3).enumerate() {
+                        person_medals[entry.person_indices[competitor_index]][place] += 1;
+                    }
+                }
+
+                for (counts, medals) in self.person_counts.iter_mut().zip(&person_medals) {
+                    for (total, count) in counts.medals.iter_mut().zip(medals) {
+                        *total += count;
+                    }
+
+                    counts.win_any += (medals[0] > 0) as u32;
+                    counts.podium_any += (medals.iter().sum::<u32>() > 0) as u32;
+                }
+
+                self.groups.add_simulation(&person_medals);
+
+                let finishes: Vec<EventFinish> = self
+                    .events
+                    .iter()
+                    .zip(&placements)
+                    .map(|(entry, event_placements)| EventFinish {
+                        placement: &event_placements[lane],
+                        person_indices: &entry.person_indices,
+                    })
+                    .collect();
+
+                Self::update_overall_rankings(&mut self.person_counts, &finishes);
+            }
+
+            self.num_simulations_run += 4;
+        }
+    }
+
+    fn update_overall_rankings(person_counts: &mut [PersonCounts], finishes: &[EventFinish]) {
+        let num_persons = person_counts.len();
+
+        let kinch_scores = calc_kinch_scores(finishes, num_persons);
+        let sums_of_ranks = calc_sum_of_ranks(finishes, num_persons);
+
+        let kinch_placements = calc_overall_placements(&kinch_scores, true);
+        let sum_of_ranks_placements = calc_overall_placements(&sums_of_ranks, false);
+
+        for (person_index, counts) in person_counts.iter_mut().enumerate() {
+            counts.total_kinch_score += kinch_scores[person_index] as f64;
+            counts.total_sum_of_ranks += sums_of_ranks[person_index] as u64;
+            counts.kinch_placements[kinch_placements[person_index]] += 1;
+            counts.sum_of_ranks_placements[sum_of_ranks_placements[person_index]] += 1;
+        }
+    }
+
+    // Groups persons by the given labels (e.g. team names), falling back to their country
+    pub fn set_groups(&mut self, labels: &HashMap<String, String>) {
+        let groups: Vec<Option<String>> = self
+            .persons
+            .iter()
+            .map(|person| {
+                labels
+                    .get(&person.wca_id)
+                    .cloned()
+                    .or_else(|| person.country.clone())
+            })
+            .collect();
+
+        self.groups = GroupMedalTable::new(&groups);
+    }
+
+    pub fn generate_wasm_output(&mut self) -> CompetitionWASMOutput {
+        let trials = self.num_simulations_run.max(1) as f32;
+
+        let persons = self
+            .persons
+            .iter()
+            .enumerate()
+            .map(|(person_index, person)| {
+                let counts = &self.person_counts[person_index];
+                let [golds, silvers, bronzes] = counts.medals.map(|count| count as f32 / trials);
+
+                PersonOutcomeWASMOutput {
+                    wca_id: person.wca_id.clone(),
+                    name: person.name.clone(),
+                    num_events: self
+                        .events
+                        .iter()
+                        .filter(|entry| entry.person_indices.contains(&person_index))
+                        .count() as u32,
+                    expected_golds: golds,
+                    expected_silvers: silvers,
+                    expected_bronzes: bronzes,
+                    expected_medals: golds + silvers + bronzes,
+                    win_any_probability: counts.win_any as f32 / trials,
+                    podium_any_probability: counts.podium_any as f32 / trials,
+                    expected_kinch_score: (counts.total_kinch_score / trials as f64) as f32,
+                    expected_sum_of_ranks: (counts.total_sum_of_ranks as f64 / trials as f64)
+                        as f32,
+                    kinch_placement_dist: counts.kinch_placements.clone(),
+                    sum_of_ranks_placement_dist: counts.sum_of_ranks_placements.clone(),
+                }
+            })
+            .collect();
+
+        let events = self
+            .events
+            .iter_mut()
+            .map(|entry| EventOutcomeWASMOutput {
+                event_id: entry.event.id().to_string(),
+                results: entry.simulator.generate_wasm_output(),
+            })
+            .collect();
+
+        CompetitionWASMOutput {
+            num_simulations: self.num_simulations_run,
+            persons,
+            groups: self.groups.generate_wasm_output(self.num_simulations_run),
+            events,
+        }
+    }
+}
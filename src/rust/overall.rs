@@ -0,0 +1,95 @@
+use crate::simd::DNF_VALUE;
+use crate::simulation::SimulatedPlacement;
+
+// One event's finish in a single simulation, with each competitor mapped to a person
+pub struct EventFinish<'a> {
+    pub placement: &'a SimulatedPlacement,
+    pub person_indices: &'a [usize],
+}
+
+// Kinch score: the mean across every event of 100 * winning result / own result, where a DNF or
+// not competing scores 0. Higher is better.
+pub fn calc_kinch_scores(finishes: &[EventFinish], num_persons: usize) -> Vec<f32> {
+    let mut scores = vec![0.0; num_persons];
+
+    for finish in finishes {
+        let Some(&winner) = finish.placement.order.first() else {
+            continue;
+        };
+        let winning_result = finish.placement.results[winner];
+
+        for (competitor_index, &result) in finish.placement.results.iter().enumerate() {
+            if result <= 0 || result >= DNF_VALUE {
+                continue;
+            }
+
+            scores[finish.person_indices[competitor_index]] +=
+                100.0 * winning_result as f32 / result as f32;
+        }
+    }
+
+    let num_events = finishes.len().max(1) as f32;
+
+    scores.iter_mut().for_each(|score| *score /= num_events);
+
+    scores
+}
+
+// Sum of ranks across every event, where not competing in an event counts as one place behind
+// the last competitor. Lower is better.
+pub fn calc_sum_of_ranks(finishes: &[EventFinish], num_persons: usize) -> Vec<u32> {
+    let mut sums = vec![0; num_persons];
+
+    for finish in finishes {
+        let num_competitors = finish.placement.order.len() as u32;
+        let mut competed = vec![false; num_persons];
+
+        for (position, &competitor_index) in finish.placement.order.iter().enumerate() {
+            let person_index = finish.person_indices[competitor_index];
+
+            sums[person_index] += position as u32 + 1;
+            competed[person_index] = true;
+        }
+
+        for (sum, _) in sums
+            .iter_mut()
+            .zip(competed)
+            .filter(|(_, competed)| !competed)
+        {
+            *sum += num_competitors + 1;
+        }
+    }
+
+    sums
+}
+
+// Overall placement of each person (0 for first), from scores sorted best first. Tied persons
+// share the best of their places, so the order they were listed in doesn't matter.
+pub fn calc_overall_placements<T: PartialOrd>(scores: &[T], higher_is_better: bool) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+
+    order.sort_by(|&a, &b| {
+        let ordering = scores[a]
+            .partial_cmp(&scores[b])
+            .unwrap_or(std::cmp::Ordering::Equal);
+
+        if higher_is_better {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+
+    let mut placements = vec![0; scores.len()];
+
+    for (place, &person_index) in order.iter().enumerate() {
+        placements[person_index] = match place.checked_sub(1).map(|previous| order[previous]) {
+            Some(previous_index) if scores[previous_index] == scores[person_index] => {
+                placements[previous_index]
+            }
+            _ => place,
+        };
+    }
+
+    placements
+}
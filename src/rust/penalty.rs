@@ -0,0 +1,41 @@
+use serde::Deserialize;
+
+// Result codes used by the WCA for attempts without a time
+pub const DNF_RESULT: i32 = -1;
+pub const DNS_RESULT: i32 = -2;
+
+// A +2 penalty, in centiseconds
+pub const PLUS_TWO: i32 = 200;
+
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum Penalty {
+    #[serde(rename = "+2")]
+    PlusTwo,
+    #[serde(rename = "DNF")]
+    Dnf,
+    #[serde(rename = "DNS")]
+    Dns,
+}
+
+// An entered attempt, either a result as the WCA stores it (0 for not done yet) or a time
+// with an explicit penalty, e.g. { "time": 1234, "penalty": "+2" }
+#[derive(Deserialize, Debug, Copy, Clone)]
+#[serde(untagged)]
+pub enum EnteredAttempt {
+    Result(i32),
+    Penalized { time: i32, penalty: Option<Penalty> },
+}
+
+impl EnteredAttempt {
+    pub fn to_result(self) -> i32 {
+        match self {
+            EnteredAttempt::Result(result) => result,
+            EnteredAttempt::Penalized { time, penalty } => match penalty {
+                None => time,
+                Some(Penalty::PlusTwo) => time + PLUS_TWO,
+                Some(Penalty::Dnf) => DNF_RESULT,
+                Some(Penalty::Dns) => DNS_RESULT,
+            },
+        }
+    }
+}
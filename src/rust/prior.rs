@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+
+use crate::competitor::CompetitorStats;
+
+// Competitors need at least this many effective results in both events to be used when fitting
+// the population ratio between two events
+const MIN_PAIR_SAMPLE_SIZE: f32 = 5.0;
+const MIN_PAIRS: usize = 3;
+
+#[derive(Deserialize, Debug, Copy, Clone)]
+#[serde(default)]
+pub struct CrossEventPriorOptions {
+    // How many results the prior is worth
+    pub strength: f32,
+    // The prior is only used when the target event has fewer effective results than this
+    pub max_sample_size: f32,
+}
+
+impl Default for CrossEventPriorOptions {
+    fn default() -> Self {
+        Self {
+            strength: 10.0,
+            max_sample_size: 30.0,
+        }
+    }
+}
+
+// Population relationship between a competitor's results in a source and target event
+#[derive(Debug, Copy, Clone)]
+pub struct RatioModel {
+    log_mean_ratio: f32,
+    log_mean_ratio_variance: f32,
+    log_stdev_ratio: f32,
+    target_dnf_rate: f32,
+}
+
+impl RatioModel {
+    // Fits the model from (target, source) stats of competitors who have results in both events
+    pub fn fit(pairs: &[(&CompetitorStats, &CompetitorStats)]) -> Option<Self> {
+        let pairs: Vec<_> = pairs
+            .iter()
+            .filter(|(target, source)| {
+                target.effective_sample_size >= MIN_PAIR_SAMPLE_SIZE
+                    && source.effective_sample_size >= MIN_PAIR_SAMPLE_SIZE
+                    && target.mean > 0.0
+                    && source.mean > 0.0
+                    && target.stdev > 0.0
+                    && source.stdev > 0.0
+            })
+            .collect();
+
+        if pairs.len() < MIN_PAIRS {
+            return None;
+        }
+
+        let n = pairs.len() as f32;
+
+        let log_mean_ratios: Vec<f32> = pairs
+            .iter()
+            .map(|(target, source)| (target.model_mean() / source.model_mean()).ln())
+            .collect();
+
+        let log_mean_ratio = log_mean_ratios.iter().sum::<f32>() / n;
+        let log_mean_ratio_variance = log_mean_ratios
+            .iter()
+            .map(|ratio| (ratio - log_mean_ratio).powi(2))
+            .sum::<f32>()
+            / (n - 1.0);
+
+        let log_stdev_ratio = pairs
+            .iter()
+            .map(|(target, source)| {
+                (target.model_variance().sqrt() / source.model_variance().sqrt()).ln()
+            })
+            .sum::<f32>()
+            / n;
+
+        let target_dnf_rate = pairs.iter().map(|(target, _)| target.dnf_rate).sum::<f32>() / n;
+
+        Some(Self {
+            log_mean_ratio,
+            log_mean_ratio_variance,
+            log_stdev_ratio,
+            target_dnf_rate,
+        })
+    }
+
+    pub fn spread(&self) -> f32 {
+        self.log_mean_ratio_variance
+    }
+
+    pub fn predict(
+        &self,
+        source: &CompetitorStats,
+        options: &CrossEventPriorOptions,
+    ) -> EventPrior {
+        EventPrior {
+            mean: source.model_mean() * self.log_mean_ratio.exp(),
+            stdev: source.model_variance().sqrt() * self.log_stdev_ratio.exp(),
+            skew: source.skew,
+            dnf_rate: self.target_dnf_rate,
+            strength: options.strength,
+            max_sample_size: options.max_sample_size,
+        }
+    }
+}
+
+// Estimate of a competitor's distribution in an event, derived from a related event
+#[derive(Serialize, Debug, Copy, Clone)]
+pub struct EventPrior {
+    pub mean: f32,
+    pub stdev: f32,
+    pub skew: f32,
+    pub dnf_rate: f32,
+    pub strength: f32,
+    pub max_sample_size: f32,
+}
+
+impl EventPrior {
+    pub fn applies_to(&self, stats: &CompetitorStats) -> bool {
+        stats.effective_sample_size < self.max_sample_size
+    }
+
+    // Combines the prior with fitted stats, weighting each by its (effective) sample size
+    pub fn blend(&self, stats: &CompetitorStats) -> CompetitorStats {
+        let data_weight =
+            stats.effective_sample_size / (stats.effective_sample_size + self.strength);
+        let prior_weight = 1.0 - data_weight;
+
+        let data_mean = stats.model_mean();
+        let data_variance = stats.model_variance();
+
+        let mean = data_weight * data_mean + prior_weight * self.mean;
+        let variance = data_weight * data_variance
+            + prior_weight * self.stdev.powi(2)
+            + data_weight * prior_weight * (data_mean - self.mean).powi(2);
+
+        let mut blended = stats.clone();
+        blended.set_model_moments(mean, variance);
+        blended.dnf_rate = data_weight * stats.dnf_rate + prior_weight * self.dnf_rate;
+        blended.prior = Some(*self);
+
+        blended
+    }
+
+    // Stats for a competitor with no usable results in the target event
+    pub fn to_stats(self) -> CompetitorStats {
+        let mut stats = CompetitorStats {
+            location: 0.0,
+            shape: 1.0,
+            skew: self.skew,
+            dnf_rate: self.dnf_rate,
+            plus_two_rate: 0.0,
+            mean: 0.0,
+            stdev: 0.0,
+            num_non_dnf_results: 0,
+            effective_sample_size: 0.0,
+            num_trimmed: 0,
+            prior: Some(self),
+        };
+
+        stats.set_model_moments(self.mean, self.stdev.powi(2));
+
+        stats
+    }
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+use crate::event_simulator::SimulatedRound;
+
+#[derive(Deserialize, Debug, Copy, Clone, Default)]
+#[serde(default)]
+pub struct RecordPair {
+    pub single: Option<i32>,
+    pub average: Option<i32>,
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, Default)]
+#[serde(default)]
+pub struct RegionalRecords {
+    pub national: Option<RecordPair>,
+    pub continental: Option<RecordPair>,
+}
+
+// Records a competitor could break, from personal up to world records
+#[derive(Debug, Copy, Clone, Default)]
+pub struct RecordThresholds {
+    pub personal: RecordPair,
+    pub national: RecordPair,
+    pub continental: RecordPair,
+    pub world: RecordPair,
+}
+
+impl RecordThresholds {
+    fn levels(&self) -> [&RecordPair; 4] {
+        [
+            &self.personal,
+            &self.national,
+            &self.continental,
+            &self.world,
+        ]
+    }
+}
+
+// Number of simulations in which each record was broken, indexed like RecordThresholds::levels
+#[derive(Debug, Copy, Clone, Default)]
+pub struct RecordCounts {
+    single: [u32; 4],
+    average: [u32; 4],
+}
+
+impl RecordCounts {
+    pub fn add_round(&mut self, thresholds: &RecordThresholds, round: &SimulatedRound) {
+        for (level, record) in thresholds.levels().iter().enumerate() {
+            if let Some(single_record) = record.single {
+                self.single[level] += Self::count_broken(&round.best_single, single_record);
+            }
+
+            if let Some(average_record) = record.average {
+                self.average[level] += Self::count_broken(&round.average, average_record);
+            }
+        }
+    }
+
+    // Records have to be strictly beaten, DNFs never break them
+    fn count_broken(results: &[i32; 4], record: i32) -> u32 {
+        results
+            .iter()
+            .filter(|&&result| result > 0 && result < record)
+            .count() as u32
+    }
+
+    pub fn probabilities(
+        &self,
+        thresholds: &RecordThresholds,
+        num_simulations: u32,
+    ) -> RecordProbabilities {
+        let trials = num_simulations.max(1) as f32;
+        let levels = thresholds.levels();
+
+        let pair = |level: usize| RecordPairProbability {
+            single: levels[level]
+                .single
+                .map(|_| self.single[level] as f32 / trials),
+            average: levels[level]
+                .average
+                .map(|_| self.average[level] as f32 / trials),
+        };
+
+        RecordProbabilities {
+            personal: pair(0),
+            national: pair(1),
+            continental: pair(2),
+            world: pair(3),
+        }
+    }
+}
+
+// Probabilities are None when the record isn't known
+#[derive(Serialize, Debug)]
+pub struct RecordPairProbability {
+    single: Option<f32>,
+    average: Option<f32>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RecordProbabilities {
+    personal: RecordPairProbability,
+    national: RecordPairProbability,
+    continental: RecordPairProbability,
+    world: RecordPairProbability,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct RecordInput {
+    pub world: Option<RecordPair>,
+    // Indexed like the loaded competitors
+    pub competitors: Vec<RegionalRecords>,
+}
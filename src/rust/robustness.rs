@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+use crate::simulation::{CompetitionSimulator, RuntimeConfig};
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RobustnessOptions {
+    pub halflives: Vec<f32>,
+    // Days of results before the end date to keep, where null keeps the whole loaded range
+    pub window_days: Vec<Option<i32>>,
+    pub include_dnf: Vec<bool>,
+    // Simulations run for each scenario
+    pub num_simulations: u32,
+}
+
+impl Default for RobustnessOptions {
+    fn default() -> Self {
+        Self {
+            halflives: vec![90.0, 180.0, 365.0],
+            window_days: vec![None, Some(365)],
+            include_dnf: vec![true, false],
+            num_simulations: 10000,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ScenarioWASMOutput {
+    halflife: f32,
+    window_days: Option<i32>,
+    include_dnf: bool,
+    // Win probability of each competitor, in the loaded order
+    win_probabilities: Vec<f32>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CompetitorRobustnessWASMOutput {
+    name: String,
+    min_win_probability: f32,
+    max_win_probability: f32,
+    mean_win_probability: f32,
+    stdev_win_probability: f32,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RobustnessWASMOutput {
+    competitors: Vec<CompetitorRobustnessWASMOutput>,
+    scenarios: Vec<ScenarioWASMOutput>,
+}
+
+// Reruns the loaded competition across every combination of settings, refitting each competitor
+// from the results that were already fetched
+pub fn run_robustness_analysis(
+    simulator: &CompetitionSimulator,
+    options: &RobustnessOptions,
+) -> Result<RobustnessWASMOutput, &'static str> {
+    let mut scenarios = Vec::new();
+
+    for &halflife in options.halflives.iter().filter(|&&halflife| halflife > 0.0) {
+        for &window_days in &options.window_days {
+            let mut scenario_simulator = simulator.refit(halflife, window_days);
+
+            for &include_dnf in &options.include_dnf {
+                let mut config = RuntimeConfig {
+                    num_simulations: options.num_simulations,
+                    include_dnf,
+                    convergence: None,
+                };
+
+                scenario_simulator.run_simulations(&mut config);
+
+                scenarios.push(ScenarioWASMOutput {
+                    halflife,
+                    window_days,
+                    include_dnf,
+                    win_probabilities: scenario_simulator
+                        .outcome_probabilities()
+                        .iter()
+                        .map(|outcome| outcome.win)
+                        .collect(),
+                });
+            }
+        }
+    }
+
+    if scenarios.is_empty() {
+        return Err("No scenarios to simulate");
+    }
+
+    let competitors = simulator
+        .competitors()
+        .iter()
+        .enumerate()
+        .map(|(i, competitor)| {
+            let probabilities: Vec<f32> = scenarios
+                .iter()
+                .map(|scenario| scenario.win_probabilities[i])
+                .collect();
+
+            let count = probabilities.len() as f32;
+            let mean = probabilities.iter().sum::<f32>() / count;
+            let variance = probabilities
+                .iter()
+                .map(|probability| (probability - mean).powi(2))
+                .sum::<f32>()
+                / count;
+
+            CompetitorRobustnessWASMOutput {
+                name: competitor.name.clone(),
+                min_win_probability: probabilities.iter().copied().fold(1.0, f32::min),
+                max_win_probability: probabilities.iter().copied().fold(0.0, f32::max),
+                mean_win_probability: mean,
+                stdev_win_probability: variance.sqrt(),
+            }
+        })
+        .collect();
+
+    Ok(RobustnessWASMOutput {
+        competitors,
+        scenarios,
+    })
+}
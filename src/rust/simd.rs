@@ -1,12 +1,14 @@
 use core::arch::wasm32::{
-    f32x4, f32x4_add, f32x4_convert_i32x4, f32x4_div, f32x4_gt, f32x4_mul, f32x4_neg, f32x4_splat,
-    f32x4_sub, i32x4_extract_lane, i32x4_mul, i32x4_splat, i32x4_trunc_sat_f32x4, v128,
-    v128_bitselect,
+    f32x4, f32x4_add, f32x4_div, f32x4_gt, f32x4_lt, f32x4_mul, f32x4_neg, f32x4_splat, f32x4_sub,
+    i32x4, i32x4_add, i32x4_extract_lane, i32x4_splat, i32x4_trunc_sat_f32x4, v128, v128_bitselect,
 };
 use rand::Rng;
 use rand_distr::{Distribution, Normal, Uniform};
 
-use crate::{competitor::CompetitorStats, simulation::RuntimeConfig};
+use crate::{
+    competitor::CompetitorStats, move_count::MoveCountModel, penalty::PLUS_TWO,
+    simulation::RuntimeConfig,
+};
 
 // Represents one centisecond (1/100th of a second) worse than the highest allowable result.
 // We use this rather than i32::MAX because SIMD instructions have slightly different definitions
@@ -56,11 +58,7 @@ pub fn generate_skewnorm_vec(
 
     for i in 0..count {
         let solves = if i < entered_times.len() && entered_times[i] != 0 {
-            if entered_times[i] < 0 {
-                i32x4_splat(DNF_VALUE)
-            } else {
-                i32x4_splat(entered_times[i])
-            }
+            splat_entered_time(entered_times[i])
         } else if let Some(stats) = stats {
             simd_gen_skewnorm(stats, rng, include_dnf)
         } else {
@@ -73,6 +71,46 @@ pub fn generate_skewnorm_vec(
     values
 }
 
+// Draws whole move counts (in hundredths of a move) for FMC
+pub fn generate_move_count_vec(
+    count: usize,
+    model: Option<&MoveCountModel>,
+    dnf_rate: f32,
+    rng: &mut impl Rng,
+    config: &RuntimeConfig,
+    entered_times: &[i32],
+) -> Vec<v128> {
+    (0..count)
+        .map(|i| {
+            if i < entered_times.len() && entered_times[i] != 0 {
+                return splat_entered_time(entered_times[i]);
+            }
+
+            let Some(model) = model else {
+                return i32x4_splat(DNF_VALUE);
+            };
+
+            let mut draw = || {
+                if config.include_dnf && rng.random::<f32>() < dnf_rate {
+                    DNF_VALUE
+                } else {
+                    model.sample(rng)
+                }
+            };
+
+            i32x4(draw(), draw(), draw(), draw())
+        })
+        .collect()
+}
+
+fn splat_entered_time(entered_time: i32) -> v128 {
+    if entered_time < 0 {
+        i32x4_splat(DNF_VALUE)
+    } else {
+        i32x4_splat(entered_time)
+    }
+}
+
 fn gen_random_f32x4<T>(dist: &T, rng: &mut impl Rng) -> v128
 where
     T: Distribution<f32>,
@@ -85,17 +123,6 @@ where
     f32x4(v1, v2, v3, v4)
 }
 
-// Truncates vales down to the nearest factor of 100 (used for FMC)
-pub fn i32x4_truncate_down_100(vec: v128) -> v128 {
-    let factor_div = f32x4_splat(100.0);
-    let factor_mul = i32x4_splat(100);
-
-    let truncated = f32x4_div(f32x4_convert_i32x4(vec), factor_div);
-    let truncated_i32 = i32x4_trunc_sat_f32x4(truncated);
-
-    i32x4_mul(truncated_i32, factor_mul)
-}
-
 pub fn i32x4_to_slice(vec: v128) -> [i32; 4] {
     [
         i32x4_extract_lane::<0>(vec),
@@ -139,7 +166,18 @@ pub fn simd_gen_skewnorm(
     let u2 = f32x4_conditional_negate(u1, u0);
     let u3 = f32x4_add(u2, f32x4_splat(stats.location));
 
-    let results_i32 = i32x4_trunc_sat_f32x4(u3);
+    let mut results_i32 = i32x4_trunc_sat_f32x4(u3);
+
+    if stats.plus_two_rate > 0.0 {
+        let uniform_dist =
+            Uniform::new(0.0, 1.0).expect("Failed to initialize uniform distribution");
+        let r = gen_random_f32x4(&uniform_dist, rand_source);
+
+        let mask = f32x4_lt(r, f32x4_splat(stats.plus_two_rate));
+        let penalties = v128_bitselect(i32x4_splat(PLUS_TWO), i32x4_splat(0), mask);
+
+        results_i32 = i32x4_add(results_i32, penalties);
+    }
 
     if !include_dnf {
         return results_i32;
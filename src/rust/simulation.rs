@@ -1,19 +1,32 @@
+use core::arch::wasm32::v128;
 use rand::rng;
 use rand::rngs::ThreadRng;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::iter::zip;
 
-use crate::calc::{find_lowest_indices, transpose_solves};
-use crate::competitor::Competitor;
-use crate::event::EventType;
+use crate::calc::{
+    calc_standard_error, calc_wilson_interval, find_lowest_indices, transpose_solves, Z_95,
+};
+use crate::competitor::{Competitor, CompetitorStats, StatsOverride};
+use crate::diagnostics::FitDiagnostics;
+use crate::event::{EventType, Mo3Event};
 use crate::event_simulator::{Ao5Simulation, Bo3Simulation, EventSimulation, Mo3Simulation};
-use crate::histogram::Histogram;
+use crate::heavy_hitters::SpaceSaving;
+use crate::histogram::{
+    calc_freedman_diaconis_width, BinWidth, Histogram, HistogramOptions, DEFAULT_BIN_WIDTH,
+};
+use crate::penalty::EnteredAttempt;
+use crate::records::{RecordCounts, RecordInput, RecordPair, RecordProbabilities};
+use crate::summary::{calc_rank_percentiles, Percentiles, ResultDistribution};
+use crate::unofficial::UnofficialResults;
 
 #[derive(Serialize, Debug)]
 pub struct SimulationWASMOutput {
     name: String,
     sample_size: u32,
+    num_trimmed: u32,
+    overridden: bool,
     win_count: u32,
     pod_count: u32,
     total_rank: u32,
@@ -21,39 +34,152 @@ pub struct SimulationWASMOutput {
     rank_dist: Vec<u32>,
     hist_values_single: HashMap<i32, i32>,
     hist_values_average: HashMap<i32, i32>,
+    // Singles including entered attempts, and singles of only the attempts still to be done
+    hist_single: Histogram,
+    hist_remaining: Histogram,
+    hist_average: Histogram,
+    num_simulations: u32,
+    win_se: f32,
+    pod_se: f32,
+    win_ci: (f32, f32),
+    pod_ci: (f32, f32),
+    rank_dist_ci: Vec<(f32, f32)>,
+    record_probabilities: RecordProbabilities,
+    // Results are in the same units as entered results, with None for DNF
+    single_percentiles: Percentiles<Option<i32>>,
+    average_percentiles: Percentiles<Option<i32>>,
+    rank_percentiles: Percentiles<u32>,
+    // Means over simulations without a DNF
+    expected_best_single: Option<f32>,
+    expected_average: Option<f32>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FitDiagnosticsWASMOutput {
+    name: String,
+    diagnostics: Option<FitDiagnostics>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CompetitorModelWASMOutput {
+    name: String,
+    last_result_date: Option<String>,
+    model: Option<CompetitorStats>,
+    overridden: bool,
+    adjusted_model: Option<CompetitorStats>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct HeadToHeadWASMOutput {
+    names: Vec<String>,
+    num_simulations: u32,
+    // wins[a][b] is the number of simulations where competitor a placed ahead of competitor b
+    wins: Vec<Vec<u32>>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PodiumOutcomeWASMOutput {
+    names: Vec<String>,
+    indices: Vec<usize>,
+    count: u32,
+    probability: f32,
+    // The count may be overestimated by up to this much
+    max_error: u32,
+}
+
+// Optional, more expensive statistics collected while simulating
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct TrackingOptions {
+    pub head_to_head: bool,
+    pub podium_outcomes: Option<PodiumTrackingOptions>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PodiumTrackingOptions {
+    // Number of top places in each tracked outcome
+    pub places: usize,
+    // Number of distinct outcomes kept in memory
+    pub capacity: usize,
+}
+
+impl Default for PodiumTrackingOptions {
+    fn default() -> Self {
+        Self {
+            places: 3,
+            capacity: 1000,
+        }
+    }
 }
 
 pub struct CompetitionSimulator {
+    event: EventType,
     event_simulator: Box<dyn EventSimulation>,
     competitors_data: Vec<Competitor>,
     simulation_results: Option<Vec<SimulationResult>>,
+    num_simulations_run: u32,
+    // Config of a simulation being run incrementally with step_simulations
+    active_config: Option<RuntimeConfig>,
+    tracking: TrackingOptions,
+    histogram_options: HistogramOptions,
+    // Row-major matrix of pairwise wins, when head-to-head tracking is enabled
+    head_to_head: Option<Vec<u32>>,
+    // Counts of ordered top-k finishes, when podium outcome tracking is enabled
+    podium_outcomes: Option<SpaceSaving<Vec<usize>>>,
     rng: ThreadRng,
 }
 
 pub struct RuntimeConfig {
     pub num_simulations: u32,
     pub include_dnf: bool,
+    // When set, num_simulations is the maximum and simulation stops once converged
+    pub convergence: Option<ConvergenceTarget>,
+}
+
+pub struct ConvergenceTarget {
+    // Largest acceptable 95% confidence interval half-width for win and podium probabilities
+    pub half_width: f32,
+    pub batch_size: u32,
+}
+
+pub struct OutcomeProbabilities {
+    pub win: f32,
+    pub podium: f32,
+    pub expected_rank: f32,
+}
+
+// Finishing order (competitor indices, winner first) and results of one simulation
+pub struct SimulatedPlacement {
+    pub order: Vec<usize>,
+    pub results: Vec<i32>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ResultHistograms {
-    pub hist_single: Histogram,
+    // Simulated attempts only, without any entered attempts
+    pub hist_remaining: Histogram,
     pub hist_average: Histogram,
 }
 
+#[derive(Clone)]
 pub struct SimulationResult {
     win_count: u32,
     pod_count: u32,
     total_rank: u32,
     rank_dist: Vec<u32>,
     histograms: ResultHistograms,
+    record_counts: RecordCounts,
+    singles: ResultDistribution,
+    best_singles: ResultDistribution,
+    averages: ResultDistribution,
 }
 
 impl SimulationResult {
-    pub fn new(num_competitors: usize, hist_min: i32, hist_max: i32) -> Self {
+    pub fn new(num_competitors: usize, hist_min: i32, hist_max: i32, bin_width: i32) -> Self {
         let histograms = ResultHistograms {
-            hist_single: Histogram::new_with_bounds(hist_min, hist_max),
-            hist_average: Histogram::new_with_bounds(hist_min, hist_max),
+            hist_remaining: Histogram::new(hist_min, hist_max, bin_width),
+            hist_average: Histogram::new(hist_min, hist_max, bin_width),
         };
 
         Self {
@@ -62,12 +188,22 @@ impl SimulationResult {
             total_rank: 0,
             rank_dist: vec![0; num_competitors],
             histograms,
+            record_counts: RecordCounts::default(),
+            singles: ResultDistribution::default(),
+            best_singles: ResultDistribution::default(),
+            averages: ResultDistribution::default(),
         }
     }
 }
 
 impl CompetitionSimulator {
-    pub fn new(event: EventType, competitors: Vec<Competitor>) -> Self {
+    pub fn new(event: EventType, mut competitors: Vec<Competitor>) -> Self {
+        if event == EventType::Mo3(Mo3Event::F333) {
+            for competitor in &mut competitors {
+                competitor.use_move_count_model();
+            }
+        }
+
         let event_simulator: Box<dyn EventSimulation> = match event {
             EventType::Ao5(_) => Box::new(Ao5Simulation),
             EventType::Mo3(mo3_event) => Box::new(Mo3Simulation { event: mo3_event }),
@@ -75,14 +211,21 @@ impl CompetitionSimulator {
         };
 
         Self {
+            event,
             event_simulator,
             competitors_data: competitors,
             simulation_results: None,
+            num_simulations_run: 0,
+            active_config: None,
+            tracking: TrackingOptions::default(),
+            histogram_options: HistogramOptions::default(),
+            head_to_head: None,
+            podium_outcomes: None,
             rng: rng(),
         }
     }
 
-    fn get_default_results(&self) -> Vec<SimulationResult> {
+    fn get_default_results(&self, num_simulations: u32) -> Vec<SimulationResult> {
         let (hist_min, hist_max) = self
             .competitors_data
             .iter()
@@ -93,41 +236,338 @@ impl CompetitionSimulator {
 
         let num_competitors = self.competitors_data.len();
 
-        (0..num_competitors)
-            .map(|_| SimulationResult::new(num_competitors, hist_min, hist_max))
+        self.competitors_data
+            .iter()
+            .map(|competitor| {
+                let bin_width = match self.histogram_options.bin_width {
+                    BinWidth::Fixed { width } => width,
+                    // Incremental runs started without an expected count can't size bins from it
+                    BinWidth::FreedmanDiaconis if num_simulations == 0 => DEFAULT_BIN_WIDTH,
+                    BinWidth::FreedmanDiaconis => {
+                        competitor
+                            .get_interquartile_range()
+                            .map_or(1, |interquartile_range| {
+                                calc_freedman_diaconis_width(
+                                    interquartile_range,
+                                    num_simulations * self.num_attempts() as u32,
+                                )
+                            })
+                    }
+                };
+
+                SimulationResult::new(num_competitors, hist_min, hist_max, bin_width)
+            })
             .collect()
     }
 
     pub fn run_simulations(&mut self, config: &mut RuntimeConfig) {
-        self.simulation_results = Some(self.get_default_results());
+        self.reset_results(config.num_simulations);
+        self.active_config = None;
+
+        let batch_size = match &config.convergence {
+            Some(target) => target.batch_size.max(4),
+            None => config.num_simulations,
+        };
+
+        while self.num_simulations_run + 4 <= config.num_simulations {
+            let remaining = config.num_simulations - self.num_simulations_run;
+
+            self.simulate(config, batch_size.min(remaining));
+
+            match &config.convergence {
+                Some(target) if self.max_half_width() > target.half_width => continue,
+                _ => break,
+            }
+        }
+    }
+
+    pub fn start_simulations(&mut self, config: RuntimeConfig) {
+        self.reset_results(config.num_simulations);
+        self.active_config = Some(config);
+    }
+
+    pub fn reset_results(&mut self, num_simulations: u32) {
+        let num_competitors = self.competitors_data.len();
+
+        self.simulation_results = Some(self.get_default_results(num_simulations));
+        self.num_simulations_run = 0;
+        self.head_to_head = self
+            .tracking
+            .head_to_head
+            .then(|| vec![0; num_competitors * num_competitors]);
+        self.podium_outcomes = self
+            .tracking
+            .podium_outcomes
+            .as_ref()
+            .map(|options| SpaceSaving::new(options.capacity));
+    }
+
+    pub fn set_tracking_options(&mut self, tracking: TrackingOptions) {
+        self.tracking = tracking;
+    }
 
-        for _ in 0..config.num_simulations / 4 {
-            let sim_results = self.run_simulation_batch(config);
-            self.update_rankings(sim_results);
+    pub fn set_histogram_options(&mut self, histogram_options: HistogramOptions) {
+        self.histogram_options = histogram_options;
+    }
+
+    // Runs more simulations (rounded up to a multiple of 4) on top of those already run
+    pub fn step_simulations(&mut self, num_simulations: u32) -> Result<(), &'static str> {
+        let mut config = self
+            .active_config
+            .take()
+            .ok_or("No simulation in progress")?;
+
+        self.simulate(&mut config, num_simulations.div_ceil(4) * 4);
+        self.active_config = Some(config);
+
+        Ok(())
+    }
+
+    pub fn cancel_simulations(&mut self) {
+        self.active_config = None;
+        self.simulation_results = None;
+        self.num_simulations_run = 0;
+        self.head_to_head = None;
+        self.podium_outcomes = None;
+    }
+
+    fn simulate(&mut self, config: &mut RuntimeConfig, num_simulations: u32) {
+        for _ in 0..num_simulations / 4 {
+            self.simulate_placements(config);
         }
     }
 
+    // Runs 4 simulations, recording their results and returning how each one finished
+    pub fn simulate_placements(&mut self, config: &mut RuntimeConfig) -> Vec<SimulatedPlacement> {
+        let sim_results = self.run_simulation_batch(config);
+        let placements = self.update_rankings(sim_results);
+        self.num_simulations_run += 4;
+
+        placements
+    }
+
+    // Each competitor's outcomes over the simulations run so far
+    pub fn outcome_probabilities(&self) -> Vec<OutcomeProbabilities> {
+        let trials = self.num_simulations_run.max(1) as f32;
+
+        self.simulation_results
+            .iter()
+            .flatten()
+            .map(|results| OutcomeProbabilities {
+                win: results.win_count as f32 / trials,
+                podium: results.pod_count as f32 / trials,
+                expected_rank: results.total_rank as f32 / trials,
+            })
+            .collect()
+    }
+
+    // Widest confidence interval across every competitor's win and podium probabilities
+    fn max_half_width(&self) -> f32 {
+        let trials = self.num_simulations_run;
+
+        self.simulation_results
+            .iter()
+            .flatten()
+            .flat_map(|results| [results.win_count, results.pod_count])
+            .map(|count| {
+                let (low, high) = calc_wilson_interval(count, trials, Z_95);
+                (high - low) / 2.0
+            })
+            .fold(0.0, f32::max)
+    }
+
     pub fn generate_wasm_output(&mut self) -> Vec<SimulationWASMOutput> {
+        let results = self.simulation_results.take().unwrap();
+
+        self.build_wasm_output(results)
+    }
+
+    // Gets the output of the simulations run so far without ending the simulation
+    pub fn generate_wasm_snapshot(&self) -> Vec<SimulationWASMOutput> {
+        let results = self.simulation_results.clone().unwrap_or_default();
+
+        self.build_wasm_output(results)
+    }
+
+    fn build_wasm_output(&self, results: Vec<SimulationResult>) -> Vec<SimulationWASMOutput> {
+        let trials = self.num_simulations_run;
+
         let results: Vec<_> = self
             .competitors_data
             .iter()
-            .zip(self.simulation_results.take().unwrap())
-            .map(|(competitor, results)| SimulationWASMOutput {
-                name: competitor.name.clone(),
-                win_count: results.win_count,
-                sample_size: competitor.get_sample_size(),
-                pod_count: results.pod_count,
-                total_rank: results.total_rank,
-                mean_no_dnf: competitor.get_mean(),
-                rank_dist: results.rank_dist,
-                hist_values_single: results.histograms.hist_single.data(),
-                hist_values_average: results.histograms.hist_average.data(),
+            .zip(results)
+            .map(|(competitor, results)| {
+                let hist_single =
+                    Self::calc_single_histogram(competitor, &results.histograms, trials);
+
+                SimulationWASMOutput {
+                    name: competitor.name.clone(),
+                    win_count: results.win_count,
+                    sample_size: competitor.get_sample_size(),
+                    num_trimmed: competitor.get_num_trimmed(),
+                    overridden: competitor.is_overridden(),
+                    pod_count: results.pod_count,
+                    total_rank: results.total_rank,
+                    mean_no_dnf: competitor.get_mean(),
+                    rank_dist_ci: results
+                        .rank_dist
+                        .iter()
+                        .map(|&count| calc_wilson_interval(count, trials, Z_95))
+                        .collect(),
+                    rank_percentiles: calc_rank_percentiles(&results.rank_dist),
+                    rank_dist: results.rank_dist,
+                    num_simulations: trials,
+                    win_se: calc_standard_error(results.win_count, trials),
+                    pod_se: calc_standard_error(results.pod_count, trials),
+                    win_ci: calc_wilson_interval(results.win_count, trials, Z_95),
+                    pod_ci: calc_wilson_interval(results.pod_count, trials, Z_95),
+                    record_probabilities: results
+                        .record_counts
+                        .probabilities(&competitor.records, trials),
+                    single_percentiles: results.singles.percentiles(),
+                    average_percentiles: results.averages.percentiles(),
+                    expected_best_single: results.best_singles.mean(),
+                    expected_average: results.averages.mean(),
+                    hist_values_single: hist_single.to_sparse(),
+                    hist_single,
+                    hist_values_average: results.histograms.hist_average.to_sparse(),
+                    hist_remaining: results.histograms.hist_remaining,
+                    hist_average: results.histograms.hist_average,
+                }
             })
             .collect();
 
         results
     }
 
+    // Entered attempts are added as point masses, counted once per simulation
+    fn calc_single_histogram(
+        competitor: &Competitor,
+        histograms: &ResultHistograms,
+        trials: u32,
+    ) -> Histogram {
+        let mut hist_single = histograms.hist_remaining.clone();
+
+        for &time in competitor.entered_results.iter().filter(|&&time| time > 0) {
+            hist_single.merge(&Histogram::point_mass(time, trials));
+        }
+
+        hist_single
+    }
+
+    pub fn generate_fit_output(&self) -> Vec<FitDiagnosticsWASMOutput> {
+        self.competitors_data
+            .iter()
+            .map(|competitor| FitDiagnosticsWASMOutput {
+                name: competitor.name.clone(),
+                diagnostics: competitor.fit_diagnostics(),
+            })
+            .collect()
+    }
+
+    pub fn generate_model_output(&self) -> Vec<CompetitorModelWASMOutput> {
+        self.competitors_data
+            .iter()
+            .map(|competitor| CompetitorModelWASMOutput {
+                name: competitor.name.clone(),
+                last_result_date: competitor.get_last_result_date(),
+                model: competitor.stats.clone(),
+                overridden: competitor.is_overridden(),
+                adjusted_model: competitor
+                    .is_overridden()
+                    .then(|| competitor.model_stats().cloned())
+                    .flatten(),
+            })
+            .collect()
+    }
+
+    pub fn generate_head_to_head_output(&self) -> Option<HeadToHeadWASMOutput> {
+        let head_to_head = self.head_to_head.as_ref()?;
+        let num_competitors = self.competitors_data.len();
+
+        Some(HeadToHeadWASMOutput {
+            names: self
+                .competitors_data
+                .iter()
+                .map(|competitor| competitor.name.clone())
+                .collect(),
+            num_simulations: self.num_simulations_run,
+            wins: head_to_head
+                .chunks(num_competitors.max(1))
+                .map(<[u32]>::to_vec)
+                .collect(),
+        })
+    }
+
+    pub fn generate_podium_outcome_output(
+        &self,
+        num_outcomes: usize,
+    ) -> Option<Vec<PodiumOutcomeWASMOutput>> {
+        let podium_outcomes = self.podium_outcomes.as_ref()?;
+        let trials = self.num_simulations_run.max(1) as f32;
+
+        let outcomes = podium_outcomes
+            .top(num_outcomes)
+            .into_iter()
+            .map(|outcome| PodiumOutcomeWASMOutput {
+                names: outcome
+                    .item
+                    .iter()
+                    .map(|&index| self.competitors_data[index].name.clone())
+                    .collect(),
+                indices: outcome.item,
+                count: outcome.count,
+                probability: outcome.count as f32 / trials,
+                max_error: outcome.error,
+            })
+            .collect();
+
+        Some(outcomes)
+    }
+
+    // A new simulator over the same competitors, refitted with a different half-life and window
+    pub fn refit(&self, halflife: f32, window_days: Option<i32>) -> Self {
+        let competitors = self
+            .competitors_data
+            .iter()
+            .map(|competitor| competitor.refit(halflife, window_days))
+            .collect();
+
+        Self::new(self.event, competitors)
+    }
+
+    pub fn competitors(&self) -> &[Competitor] {
+        &self.competitors_data
+    }
+
+    pub fn num_attempts(&self) -> usize {
+        self.event.num_attempts()
+    }
+
+    // Smallest difference between two attempt results
+    pub fn attempt_resolution(&self) -> i32 {
+        if self.event == EventType::Mo3(Mo3Event::F333) {
+            100
+        } else {
+            1
+        }
+    }
+
+    // Simulates every competitor's attempts for 4 scenarios without recording any results
+    pub fn generate_scenario_solves(&mut self, config: &mut RuntimeConfig) -> Vec<Vec<v128>> {
+        self.competitors_data
+            .iter()
+            .map(|competitor| {
+                self.event_simulator
+                    .generate_solves(competitor, config, &mut self.rng)
+            })
+            .collect()
+    }
+
+    pub fn calculate_result(&self, solves: &[v128]) -> [i32; 4] {
+        self.event_simulator.calculate_result(solves)
+    }
+
     fn run_simulation_batch(&mut self, config: &mut RuntimeConfig) -> Vec<[i32; 4]> {
         let sim_results = self.simulation_results.as_mut().unwrap();
 
@@ -135,20 +575,33 @@ impl CompetitionSimulator {
             .iter()
             .enumerate()
             .map(|(i, data)| {
-                self.event_simulator.run_simulation(
+                let round = self.event_simulator.run_simulation(
                     data,
                     config,
                     &mut self.rng,
                     &mut sim_results[i].histograms,
-                )
+                );
+
+                let sim_result = &mut sim_results[i];
+
+                sim_result.record_counts.add_round(&data.records, &round);
+                sim_result.best_singles.add_values(&round.best_single);
+                sim_result.averages.add_values(&round.average);
+
+                for values in &round.solves {
+                    sim_result.singles.add_values(values);
+                }
+
+                round.result
             })
             .collect()
     }
 
-    fn update_rankings(&mut self, solve_results: Vec<[i32; 4]>) {
+    fn update_rankings(&mut self, solve_results: Vec<[i32; 4]>) -> Vec<SimulatedPlacement> {
         let sim_results = self.simulation_results.as_mut().unwrap();
 
         let solves_by_sim = transpose_solves(solve_results);
+        let mut placements = Vec::with_capacity(solves_by_sim.len());
 
         for avg_by_competitor in solves_by_sim {
             let indices = find_lowest_indices(avg_by_competitor.as_slice());
@@ -166,12 +619,93 @@ impl CompetitionSimulator {
                 sim_results[competitor_index].rank_dist[position] += 1;
                 sim_results[competitor_index].total_rank += (position as u32) + 1;
             }
+
+            // Update pairwise wins, using the same ordering (and tie handling) as the rankings
+            if let Some(head_to_head) = self.head_to_head.as_mut() {
+                let num_competitors = indices.len();
+
+                for (position, &winner) in indices.iter().enumerate() {
+                    for &loser in &indices[position + 1..] {
+                        head_to_head[winner * num_competitors + loser] += 1;
+                    }
+                }
+            }
+
+            if let (Some(podium_outcomes), Some(options)) = (
+                self.podium_outcomes.as_mut(),
+                self.tracking.podium_outcomes.as_ref(),
+            ) {
+                podium_outcomes.add(indices.iter().take(options.places).copied().collect());
+            }
+
+            placements.push(SimulatedPlacement {
+                order: indices,
+                results: avg_by_competitor,
+            });
+        }
+
+        placements
+    }
+
+    pub fn set_stats_override(
+        &mut self,
+        competitor_index: usize,
+        stats_override: Option<StatsOverride>,
+    ) -> Result<(), &'static str> {
+        let competitor = self
+            .competitors_data
+            .get_mut(competitor_index)
+            .ok_or("Competitor index out of range")?;
+
+        competitor.set_stats_override(stats_override);
+
+        Ok(())
+    }
+
+    pub fn set_unofficial_results(
+        &mut self,
+        competitor_index: usize,
+        unofficial_results: Option<UnofficialResults>,
+    ) -> Result<(), &'static str> {
+        let competitor = self
+            .competitors_data
+            .get_mut(competitor_index)
+            .ok_or("Competitor index out of range")?;
+
+        competitor.set_unofficial_results(unofficial_results);
+
+        Ok(())
+    }
+
+    // Record singles are given in WCA units, so FMC singles are converted to hundredths of a move
+    pub fn set_records(&mut self, records: RecordInput) {
+        let resolution = self.attempt_resolution();
+        let to_simulated = |record: Option<RecordPair>| {
+            let record = record.unwrap_or_default();
+
+            RecordPair {
+                single: record.single.map(|single| single * resolution),
+                average: record.average,
+            }
+        };
+
+        let world = to_simulated(records.world);
+
+        for (i, competitor) in self.competitors_data.iter_mut().enumerate() {
+            let regional = records.competitors.get(i).copied().unwrap_or_default();
+
+            competitor.set_regional_records(
+                to_simulated(regional.national),
+                to_simulated(regional.continental),
+                world,
+            );
         }
     }
 
-    pub fn set_entered_results(&mut self, entered_times: Vec<Vec<i32>>) {
+    pub fn set_entered_results(&mut self, entered_times: Vec<Vec<EnteredAttempt>>) {
         for (competitor, average) in zip(&mut self.competitors_data, entered_times) {
-            competitor.add_entered_results(average);
+            competitor
+                .add_entered_results(average.into_iter().map(EnteredAttempt::to_result).collect());
         }
     }
 }
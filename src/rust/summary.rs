@@ -0,0 +1,91 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::simd::DNF_VALUE;
+
+#[derive(Serialize, Debug)]
+pub struct Percentiles<T> {
+    p5: T,
+    p25: T,
+    p50: T,
+    p75: T,
+    p95: T,
+}
+
+impl<T> Percentiles<T> {
+    fn from_fn(percentile: impl Fn(f32) -> T) -> Self {
+        Self {
+            p5: percentile(0.05),
+            p25: percentile(0.25),
+            p50: percentile(0.5),
+            p75: percentile(0.75),
+            p95: percentile(0.95),
+        }
+    }
+}
+
+// Exact counts of every simulated result, so percentiles aren't limited by histogram bins or bounds
+#[derive(Debug, Clone, Default)]
+pub struct ResultDistribution {
+    counts: HashMap<i32, u32>,
+    total: u32,
+    completed_sum: f64,
+    num_completed: u32,
+}
+
+impl ResultDistribution {
+    pub fn add_values(&mut self, values: &[i32]) {
+        for &value in values {
+            let value = value.min(DNF_VALUE);
+
+            *self.counts.entry(value).or_insert(0) += 1;
+            self.total += 1;
+
+            if value < DNF_VALUE {
+                self.completed_sum += value as f64;
+                self.num_completed += 1;
+            }
+        }
+    }
+
+    // Mean of the non-DNF results
+    pub fn mean(&self) -> Option<f32> {
+        (self.num_completed > 0).then(|| (self.completed_sum / self.num_completed as f64) as f32)
+    }
+
+    // Percentiles include DNFs, which are None
+    pub fn percentiles(&self) -> Percentiles<Option<i32>> {
+        let mut counts: Vec<(i32, u32)> = self.counts.iter().map(|(&k, &v)| (k, v)).collect();
+        counts.sort_unstable();
+
+        Percentiles::from_fn(|probability| {
+            let value = calc_percentile(&counts, self.total, probability)?;
+            (value < DNF_VALUE).then_some(value)
+        })
+    }
+}
+
+// Ranks are 1-based, from a distribution where rank_dist[i] is the count of finishing in place i + 1
+pub fn calc_rank_percentiles(rank_dist: &[u32]) -> Percentiles<u32> {
+    let total = rank_dist.iter().sum();
+    let counts: Vec<(i32, u32)> = rank_dist
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| (i as i32 + 1, count))
+        .collect();
+
+    Percentiles::from_fn(|probability| {
+        calc_percentile(&counts, total, probability).unwrap_or(0) as u32
+    })
+}
+
+// Smallest value with at least the given fraction of results at or below it
+fn calc_percentile(sorted_counts: &[(i32, u32)], total: u32, probability: f32) -> Option<i32> {
+    let target = ((probability * total as f32).ceil() as u32).max(1);
+    let mut cumulative = 0;
+
+    sorted_counts.iter().find_map(|&(value, count)| {
+        cumulative += count;
+        (cumulative >= target).then_some(value)
+    })
+}
@@ -0,0 +1,297 @@
+use core::arch::wasm32::{i32x4_splat, v128};
+use serde::Serialize;
+
+use crate::competitor::Competitor;
+use crate::simd::{i32x4_to_slice, DNF_VALUE};
+use crate::simulation::{CompetitionSimulator, RuntimeConfig};
+
+const WIN_PLACE: usize = 1;
+const PODIUM_PLACE: usize = 3;
+
+// Marks a scenario where no result on the next attempt is good enough
+const IMPOSSIBLE: i32 = 0;
+
+#[derive(Serialize, Debug)]
+pub struct TargetThresholds {
+    // Slowest result on the next attempt that reaches the place even if every rival's remaining
+    // attempts are as fast as possible and the competitor's own later attempts are DNFs
+    guaranteed: Option<i32>,
+    // Slowest result on the next attempt that reaches the place in at least half of the simulated
+    // scenarios
+    likely: Option<i32>,
+    // Slowest result on the next attempt that reaches the place if every rival's remaining
+    // attempts are DNFs and the competitor's own later attempts are as fast as possible. None
+    // means the place can't be reached.
+    possible: Option<i32>,
+    // True when no result involved depends on an attempt that hasn't been entered yet
+    exact: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TargetResultWASMOutput {
+    name: String,
+    next_attempt: Option<usize>,
+    remaining_attempts: usize,
+    win: Option<TargetThresholds>,
+    podium: Option<TargetThresholds>,
+    advance: Option<TargetThresholds>,
+}
+
+// Results are in the same units as entered results. A threshold of -1 means any result, even a
+// DNF, is enough, while None means no result is enough.
+pub fn calc_target_results(
+    simulator: &mut CompetitionSimulator,
+    config: &mut RuntimeConfig,
+    num_scenarios: u32,
+    advancing: Option<usize>,
+) -> Vec<TargetResultWASMOutput> {
+    let next_attempts: Vec<Option<usize>> = simulator
+        .competitors()
+        .iter()
+        .map(|competitor| {
+            (0..simulator.num_attempts()).find(|&i| {
+                competitor
+                    .entered_results
+                    .get(i)
+                    .is_none_or(|&time| time == 0)
+            })
+        })
+        .collect();
+
+    // No place can be reached when nobody advances
+    let places: Vec<usize> = [Some(WIN_PLACE), Some(PODIUM_PLACE), advancing]
+        .into_iter()
+        .flatten()
+        .filter(|&place| place > 0)
+        .collect();
+
+    // Per competitor, per place, the slowest result that reaches the place in each scenario
+    let mut thresholds = vec![vec![Vec::new(); places.len()]; next_attempts.len()];
+
+    for _ in 0..num_scenarios.div_ceil(4) {
+        let solves = simulator.generate_scenario_solves(config);
+        let results: Vec<[i32; 4]> = solves
+            .iter()
+            .map(|competitor_solves| simulator.calculate_result(competitor_solves))
+            .collect();
+
+        for (competitor_index, next_attempt) in next_attempts.iter().enumerate() {
+            let Some(next_attempt) = *next_attempt else {
+                continue;
+            };
+
+            for lane in 0..4 {
+                let own_solves: Vec<i32> = solves[competitor_index]
+                    .iter()
+                    .map(|&solve| i32x4_to_slice(solve)[lane])
+                    .collect();
+
+                let mut others: Vec<i32> = results
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| i != competitor_index)
+                    .map(|(_, result)| result[lane])
+                    .collect();
+                others.sort_unstable();
+
+                for (place_index, &place) in places.iter().enumerate() {
+                    let threshold = calc_scenario_threshold(
+                        simulator,
+                        &own_solves,
+                        next_attempt,
+                        others.get(place - 1).copied(),
+                    );
+
+                    thresholds[competitor_index][place_index].push(threshold);
+                }
+            }
+        }
+    }
+
+    // Every competitor's results when all of their remaining attempts are as fast as possible, and
+    // when they are all DNFs
+    let fastest_attempt = simulator.attempt_resolution();
+    let best_results: Vec<i32> = simulator
+        .competitors()
+        .iter()
+        .map(|competitor| calc_filled_result(simulator, competitor, fastest_attempt))
+        .collect();
+    let worst_results: Vec<i32> = simulator
+        .competitors()
+        .iter()
+        .map(|competitor| calc_filled_result(simulator, competitor, DNF_VALUE))
+        .collect();
+
+    let is_complete = |competitor: &Competitor, skipped_attempt: Option<usize>| {
+        (0..simulator.num_attempts()).all(|i| {
+            Some(i) == skipped_attempt || competitor.entered_results.get(i).is_some_and(|&t| t != 0)
+        })
+    };
+    let num_incomplete = simulator
+        .competitors()
+        .iter()
+        .filter(|&competitor| !is_complete(competitor, None))
+        .count();
+
+    simulator
+        .competitors()
+        .iter()
+        .enumerate()
+        .zip(next_attempts)
+        .zip(thresholds)
+        .map(
+            |(((competitor_index, competitor), next_attempt), mut competitor_thresholds)| {
+                let rivals_sorted = |results: &[i32]| {
+                    let mut rivals: Vec<i32> = results
+                        .iter()
+                        .enumerate()
+                        .filter(|&(i, _)| i != competitor_index)
+                        .map(|(_, &result)| result)
+                        .collect();
+                    rivals.sort_unstable();
+                    rivals
+                };
+                let fastest_rivals = rivals_sorted(&best_results);
+                let slowest_rivals = rivals_sorted(&worst_results);
+
+                // The competitor's own next attempt is the one being solved for, so it's the only
+                // unentered attempt allowed
+                let exact = next_attempt
+                    .is_some_and(|next| is_complete(competitor, Some(next)) && num_incomplete == 1);
+
+                let mut summaries = places.iter().zip(competitor_thresholds.iter_mut()).map(
+                    |(&place, scenario_thresholds)| {
+                        let next_attempt = next_attempt?;
+
+                        let guaranteed = calc_scenario_threshold(
+                            simulator,
+                            &fill_attempts(simulator, competitor, DNF_VALUE),
+                            next_attempt,
+                            fastest_rivals.get(place - 1).copied(),
+                        );
+                        let possible = calc_scenario_threshold(
+                            simulator,
+                            &fill_attempts(simulator, competitor, fastest_attempt),
+                            next_attempt,
+                            slowest_rivals.get(place - 1).copied(),
+                        );
+
+                        summarize_thresholds(scenario_thresholds, guaranteed, possible, exact)
+                    },
+                );
+
+                TargetResultWASMOutput {
+                    name: competitor.name.clone(),
+                    next_attempt,
+                    remaining_attempts: next_attempt.map_or(0, |next| {
+                        (next..simulator.num_attempts())
+                            .filter(|&i| competitor.entered_results.get(i).is_none_or(|&t| t == 0))
+                            .count()
+                    }),
+                    win: summaries.next().flatten(),
+                    podium: summaries.next().flatten(),
+                    advance: summaries.next().flatten(),
+                }
+            },
+        )
+        .collect()
+}
+
+// Finds the slowest result on the next attempt that still beats the given rival result. Results
+// are monotonic in a single attempt, so a binary search over possible attempt results is enough.
+fn calc_scenario_threshold(
+    simulator: &CompetitionSimulator,
+    own_solves: &[i32],
+    next_attempt: usize,
+    rival_result: Option<i32>,
+) -> i32 {
+    let Some(rival_result) = rival_result else {
+        return DNF_VALUE;
+    };
+
+    let result_with = |time: i32| {
+        let solves: Vec<v128> = own_solves
+            .iter()
+            .enumerate()
+            .map(|(i, &solve)| i32x4_splat(if i == next_attempt { time } else { solve }))
+            .collect();
+
+        simulator.calculate_result(&solves)[0]
+    };
+
+    if result_with(DNF_VALUE) < rival_result {
+        return DNF_VALUE;
+    }
+
+    // FMC attempts can only be whole moves
+    let resolution = simulator.attempt_resolution();
+
+    if result_with(resolution) >= rival_result {
+        return IMPOSSIBLE;
+    }
+
+    let (mut low, mut high) = (1, DNF_VALUE / resolution);
+
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+
+        if result_with(mid * resolution) < rival_result {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    low * resolution
+}
+
+// Entered attempts, with every attempt that hasn't been entered yet set to the given result
+fn fill_attempts(simulator: &CompetitionSimulator, competitor: &Competitor, fill: i32) -> Vec<i32> {
+    (0..simulator.num_attempts())
+        .map(|i| match competitor.entered_results.get(i) {
+            Some(&time) if time < 0 => DNF_VALUE,
+            Some(&time) if time > 0 => time,
+            _ => fill,
+        })
+        .collect()
+}
+
+fn calc_filled_result(simulator: &CompetitionSimulator, competitor: &Competitor, fill: i32) -> i32 {
+    let solves: Vec<v128> = fill_attempts(simulator, competitor, fill)
+        .into_iter()
+        .map(|time| i32x4_splat(time))
+        .collect();
+
+    simulator.calculate_result(&solves)[0]
+}
+
+fn summarize_thresholds(
+    scenario_thresholds: &mut [i32],
+    guaranteed: i32,
+    possible: i32,
+    exact: bool,
+) -> Option<TargetThresholds> {
+    if scenario_thresholds.is_empty() {
+        return None;
+    }
+
+    scenario_thresholds.sort_unstable();
+
+    let to_output = |threshold: i32| match threshold {
+        IMPOSSIBLE => None,
+        DNF_VALUE => Some(-1),
+        threshold => Some(threshold),
+    };
+
+    // The median can't fall outside the boundaries, even though it comes from simulated attempts
+    let likely = scenario_thresholds[scenario_thresholds.len() / 2]
+        .max(guaranteed)
+        .min(possible);
+
+    Some(TargetThresholds {
+        guaranteed: to_output(guaranteed),
+        likely: to_output(likely),
+        possible: to_output(possible),
+        exact,
+    })
+}
@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+
+use crate::calc::{skewnorm_pdf, TrimStrategy};
+use crate::competitor::{Competitor, CompetitorStats, DatedCompetitionResult, ModelOptions};
+use crate::penalty::DNS_RESULT;
+
+// Keeps the log-likelihood finite for attempts the model gave no chance
+const MIN_PROBABILITY: f32 = 1e-6;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TuningOptions {
+    // Candidate half-lives, in days
+    pub halflives: Vec<f32>,
+    // Trim strategies to try with each half-life. Empty keeps the strategy in the model options.
+    pub trims: Vec<TrimStrategy>,
+    // Number of each competitor's most recent competitions to predict
+    pub holdout_competitions: usize,
+    pub model_options: ModelOptions,
+}
+
+impl Default for TuningOptions {
+    fn default() -> Self {
+        Self {
+            halflives: vec![30.0, 60.0, 90.0, 120.0, 180.0, 270.0, 365.0, 540.0, 730.0],
+            trims: vec![],
+            holdout_competitions: 3,
+            model_options: ModelOptions::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct TuningCandidate {
+    halflife: f32,
+    trim: TrimStrategy,
+    // Mean log-likelihood per held-out attempt, None when nothing could be scored
+    mean_log_likelihood: Option<f32>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TuningReport {
+    recommended_halflife: Option<f32>,
+    recommended_trim: Option<TrimStrategy>,
+    // Competitors with at least one held-out competition that has older results to fit on
+    num_competitors_scored: u32,
+    num_attempts_scored: u32,
+    candidates: Vec<TuningCandidate>,
+}
+
+// A held-out competition along with every older result, dated relative to it
+struct Holdout {
+    wca_id: String,
+    name: String,
+    results: Vec<i32>,
+    training: Vec<DatedCompetitionResult>,
+}
+
+// Picks the model settings that best predict each competitor's most recent competitions from
+// their results before each one. Cross-event priors are left out, since they don't depend on the
+// half-life.
+pub fn tune_halflife(competitors: &[Competitor], options: &TuningOptions) -> TuningReport {
+    let holdouts: Vec<Holdout> = competitors
+        .iter()
+        .flat_map(|competitor| build_holdouts(competitor, options.holdout_competitions))
+        .collect();
+
+    let trims = if options.trims.is_empty() {
+        vec![options.model_options.trim]
+    } else {
+        options.trims.clone()
+    };
+
+    let num_attempts_scored = holdouts
+        .iter()
+        .map(|holdout| holdout.results.len() as u32)
+        .sum::<u32>();
+
+    let candidates: Vec<TuningCandidate> = trims
+        .iter()
+        .flat_map(|&trim| {
+            options
+                .halflives
+                .iter()
+                .filter(|&&halflife| halflife > 0.0)
+                .map(move |&halflife| (halflife, trim))
+        })
+        .map(|(halflife, trim)| {
+            let model_options = ModelOptions {
+                trim,
+                ..options.model_options.clone()
+            };
+
+            let total: f64 = holdouts
+                .iter()
+                .map(|holdout| calc_log_likelihood(holdout, halflife, &model_options))
+                .sum();
+
+            TuningCandidate {
+                halflife,
+                trim,
+                mean_log_likelihood: (num_attempts_scored > 0)
+                    .then(|| (total / num_attempts_scored as f64) as f32),
+            }
+        })
+        .collect();
+
+    let best = candidates
+        .iter()
+        .filter_map(|candidate| Some((candidate, candidate.mean_log_likelihood?)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(candidate, _)| candidate);
+
+    let mut scored_competitors: Vec<&str> = holdouts
+        .iter()
+        .map(|holdout| holdout.wca_id.as_str())
+        .collect();
+    scored_competitors.dedup();
+
+    TuningReport {
+        recommended_halflife: best.map(|candidate| candidate.halflife),
+        recommended_trim: best.map(|candidate| candidate.trim),
+        num_competitors_scored: scored_competitors.len() as u32,
+        num_attempts_scored,
+        candidates,
+    }
+}
+
+fn build_holdouts(competitor: &Competitor, holdout_competitions: usize) -> Vec<Holdout> {
+    let mut results = competitor.results.clone();
+    results.sort_by_key(|result_set| result_set.days_since);
+
+    (0..holdout_competitions.min(results.len()))
+        .filter_map(|i| {
+            let holdout = &results[i];
+
+            // Only competitions on an earlier day, so same-day results don't leak in
+            let training: Vec<DatedCompetitionResult> = results[i + 1..]
+                .iter()
+                .filter(|result_set| result_set.days_since > holdout.days_since)
+                .map(|result_set| DatedCompetitionResult {
+                    days_since: result_set.days_since - holdout.days_since,
+                    ..result_set.clone()
+                })
+                .collect();
+
+            (!training.is_empty()).then(|| Holdout {
+                wca_id: competitor.wca_id.clone(),
+                name: competitor.name.clone(),
+                results: holdout
+                    .results
+                    .iter()
+                    .copied()
+                    .filter(|&result| result != 0 && result != DNS_RESULT)
+                    .collect(),
+                training,
+            })
+        })
+        .collect()
+}
+
+// Log-likelihood of the held-out attempts under the model fitted to the older results
+fn calc_log_likelihood(holdout: &Holdout, halflife: f32, model_options: &ModelOptions) -> f64 {
+    let stats = Competitor::new(
+        holdout.wca_id.clone(),
+        holdout.name.clone(),
+        holdout.training.clone(),
+        halflife,
+        model_options,
+    )
+    .stats;
+
+    holdout
+        .results
+        .iter()
+        .map(|&result| {
+            let likelihood = stats
+                .as_ref()
+                .map_or(0.0, |stats| calc_attempt_likelihood(stats, result));
+
+            likelihood.max(MIN_PROBABILITY).ln() as f64
+        })
+        .sum()
+}
+
+fn calc_attempt_likelihood(stats: &CompetitorStats, result: i32) -> f32 {
+    if result < 0 {
+        return stats.dnf_rate;
+    }
+
+    (1.0 - stats.dnf_rate) * skewnorm_pdf(result as f32, stats.skew, stats.shape, stats.location)
+}
@@ -0,0 +1,317 @@
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{competitor::DatedCompetitionResult, data::TimeRange, penalty::DNF_RESULT};
+
+// Home times are kept without their +2s, which are simulated from the rate instead
+const PLUS_TWO_MS: i64 = 2000;
+// csTimer stores the raw time and the penalty separately
+const CSTIMER_PLUS_TWO: i64 = 2000;
+// Twisty Timer stores times with the 2 seconds of a +2 already added
+const TWISTY_TIMER_PLUS_TWO: i64 = 1;
+const TWISTY_TIMER_DNF: i64 = 2;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum UnofficialFormat {
+    CsTimer,
+    TwistyTimer,
+}
+
+impl UnofficialFormat {
+    pub fn from_format_id(format_id: &str) -> Option<Self> {
+        match format_id {
+            "cstimer" => Some(Self::CsTimer),
+            "twistytimer" => Some(Self::TwistyTimer),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct UnofficialOptions {
+    // Weight of a home solve relative to an official solve from the same day
+    pub trust_weight: f32,
+    // Multiplies home times before the offset is applied, e.g. 1.05 for 5% competition nerves
+    pub scale: f32,
+    // Added to home times, in centiseconds
+    pub offset: f32,
+    // Only import this csTimer session (matched by session name or number). Required when the
+    // export has more than one session, since sessions are usually different events.
+    pub session: Option<String>,
+    // Only import this Twisty Timer puzzle (e.g. "333") and category (e.g. "Normal"). Each is
+    // required when the backup has more than one.
+    pub puzzle: Option<String>,
+    pub category: Option<String>,
+}
+
+impl Default for UnofficialOptions {
+    fn default() -> Self {
+        Self {
+            trust_weight: 0.25,
+            scale: 1.0,
+            offset: 0.0,
+            session: None,
+            puzzle: None,
+            category: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnofficialResults {
+    pub results: Vec<DatedCompetitionResult>,
+    pub trust_weight: f32,
+    pub scale: f32,
+    pub offset: f32,
+    // Share of completed home solves with a +2. Times exclude the penalty, which is simulated on
+    // top of the fit at this rate.
+    pub plus_two_rate: Option<f32>,
+}
+
+impl UnofficialResults {
+    pub fn num_solves(&self) -> usize {
+        self.results.iter().map(|day| day.results.len()).sum()
+    }
+
+    pub fn generate_import_output(&self) -> UnofficialImportWASMOutput {
+        UnofficialImportWASMOutput {
+            num_solves: self.num_solves() as u32,
+            plus_two_rate: self.plus_two_rate,
+        }
+    }
+
+    // Applies the scale and offset to a non-DNF home time
+    pub fn adjust_time(&self, time: i32) -> i32 {
+        (time as f32 * self.scale + self.offset).round() as i32
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct UnofficialImportWASMOutput {
+    num_solves: u32,
+    // Share of completed home solves with a +2, for display
+    plus_two_rate: Option<f32>,
+}
+
+struct UnofficialSolve {
+    timestamp: i64, // Seconds since epoch
+    time: i32,      // Centiseconds, excluding any +2, or -1 for a DNF
+    plus_two: bool,
+}
+
+pub fn parse_unofficial_results(
+    contents: &str,
+    format: UnofficialFormat,
+    options: &UnofficialOptions,
+    start_date: i64,
+    end_date: i64,
+) -> Result<UnofficialResults, &'static str> {
+    let solves = match format {
+        UnofficialFormat::CsTimer => parse_cstimer(contents, options.session.as_deref())?,
+        UnofficialFormat::TwistyTimer => parse_twisty_timer(
+            contents,
+            options.puzzle.as_deref(),
+            options.category.as_deref(),
+        )?,
+    };
+
+    let time_range = TimeRange::new(start_date, end_date);
+    let solves: Vec<UnofficialSolve> = solves
+        .into_iter()
+        .filter(|solve| time_range.in_time_range(solve.timestamp))
+        .collect();
+
+    let completed = solves.iter().filter(|solve| solve.time > 0).count();
+    let plus_twos = solves.iter().filter(|solve| solve.plus_two).count();
+    let plus_two_rate = (completed > 0).then(|| plus_twos as f32 / completed as f32);
+
+    Ok(UnofficialResults {
+        results: group_by_day(solves, &time_range),
+        trust_weight: options.trust_weight.max(0.0),
+        scale: options.scale,
+        offset: options.offset,
+        plus_two_rate,
+    })
+}
+
+// csTimer exports one array per session under "session1", "session2", ... where each solve is
+// [[penalty, time_ms, ...], scramble, comment, timestamp]
+fn parse_cstimer(
+    contents: &str,
+    session: Option<&str>,
+) -> Result<Vec<UnofficialSolve>, &'static str> {
+    let export: HashMap<String, Value> =
+        serde_json::from_str(contents).map_err(|_| "Failed to parse csTimer export")?;
+
+    let session_names = parse_cstimer_session_names(&export);
+
+    let sessions: Vec<(&str, &Vec<Value>)> = export
+        .iter()
+        .filter_map(|(key, value)| {
+            let session_number = key.strip_prefix("session")?;
+            let session_solves = value.as_array()?;
+
+            (!session_solves.is_empty()).then_some((session_number, session_solves))
+        })
+        .collect();
+
+    if session.is_none() && sessions.len() > 1 {
+        return Err("csTimer export has more than one session, choose one to import");
+    }
+
+    let solves = sessions
+        .into_iter()
+        .filter(|(session_number, _)| {
+            session.is_none_or(|session| {
+                session == *session_number
+                    || session_names.get(*session_number).map(String::as_str) == Some(session)
+            })
+        })
+        .flat_map(|(_, session_solves)| session_solves)
+        .filter_map(parse_cstimer_solve)
+        .collect::<Vec<_>>();
+
+    if solves.is_empty() {
+        return Err("No solves found in csTimer export");
+    }
+
+    Ok(solves)
+}
+
+fn parse_cstimer_session_names(export: &HashMap<String, Value>) -> HashMap<String, String> {
+    // Session metadata is stored as a JSON string inside the properties object
+    let session_data = export
+        .get("properties")
+        .and_then(|properties| properties.get("sessionData"))
+        .and_then(Value::as_str)
+        .and_then(|data| serde_json::from_str::<HashMap<String, Value>>(data).ok())
+        .unwrap_or_default();
+
+    session_data
+        .into_iter()
+        .filter_map(|(number, data)| {
+            let name = data.get("name")?;
+            let name = name
+                .as_str()
+                .map_or_else(|| name.to_string(), str::to_string);
+            Some((number, name))
+        })
+        .collect()
+}
+
+fn parse_cstimer_solve(solve: &Value) -> Option<UnofficialSolve> {
+    let solve = solve.as_array()?;
+    let timing = solve.first()?.as_array()?;
+
+    let penalty = timing.first()?.as_i64()?;
+    let time_ms = timing.get(1)?.as_i64()?;
+    let timestamp = solve.get(3)?.as_i64()?;
+
+    let time = match penalty {
+        -1 => DNF_RESULT,
+        _ => (time_ms / 10) as i32,
+    };
+
+    Some(UnofficialSolve {
+        timestamp,
+        time,
+        plus_two: penalty == CSTIMER_PLUS_TWO,
+    })
+}
+
+// Twisty Timer backups are semicolon-separated with quoted fields:
+// Puzzle;Category;Time(millis);Date(millis);Scramble;Penalty;Comment
+fn parse_twisty_timer(
+    contents: &str,
+    puzzle: Option<&str>,
+    category: Option<&str>,
+) -> Result<Vec<UnofficialSolve>, &'static str> {
+    let solves: Vec<(&str, &str, UnofficialSolve)> = contents
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line
+                .split(';')
+                .map(|field| field.trim_matches('"'))
+                .collect();
+
+            let time_ms: i64 = fields.get(2)?.parse().ok()?;
+            let date_ms: i64 = fields.get(3)?.parse().ok()?;
+            let penalty: i64 = fields.get(5).and_then(|p| p.parse().ok()).unwrap_or(0);
+
+            let time = match penalty {
+                TWISTY_TIMER_DNF => DNF_RESULT,
+                TWISTY_TIMER_PLUS_TWO => ((time_ms - PLUS_TWO_MS) / 10) as i32,
+                _ => (time_ms / 10) as i32,
+            };
+
+            Some((
+                *fields.first()?,
+                *fields.get(1)?,
+                UnofficialSolve {
+                    timestamp: date_ms / 1000,
+                    time,
+                    plus_two: penalty == TWISTY_TIMER_PLUS_TWO,
+                },
+            ))
+        })
+        .collect();
+
+    if solves.is_empty() {
+        return Err("No solves found in Twisty Timer export");
+    }
+
+    let (first_puzzle, first_category, _) = solves[0];
+
+    if puzzle.is_none() && solves.iter().any(|solve| solve.0 != first_puzzle) {
+        return Err("Twisty Timer export has more than one puzzle, choose one to import");
+    }
+
+    if category.is_none() && solves.iter().any(|solve| solve.1 != first_category) {
+        return Err("Twisty Timer export has more than one category, choose one to import");
+    }
+
+    let solves: Vec<UnofficialSolve> = solves
+        .into_iter()
+        .filter(|(solve_puzzle, solve_category, _)| {
+            puzzle.is_none_or(|puzzle| puzzle == *solve_puzzle)
+                && category.is_none_or(|category| category == *solve_category)
+        })
+        .map(|(_, _, solve)| solve)
+        .collect();
+
+    if solves.is_empty() {
+        return Err("No solves match the chosen puzzle and category");
+    }
+
+    Ok(solves)
+}
+
+fn group_by_day(
+    solves: Vec<UnofficialSolve>,
+    time_range: &TimeRange,
+) -> Vec<DatedCompetitionResult> {
+    let mut days: BTreeMap<i32, DatedCompetitionResult> = BTreeMap::new();
+
+    for solve in solves {
+        let days_since = time_range.days_from_cutoff(solve.timestamp);
+
+        days.entry(days_since)
+            .or_insert_with(|| DatedCompetitionResult {
+                days_since,
+                date: Utc
+                    .timestamp_opt(solve.timestamp, 0)
+                    .single()
+                    .map(|date| date.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+                results: vec![],
+                best_average: None,
+            })
+            .results
+            .push(solve.time);
+    }
+
+    days.into_values().collect()
+}
@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+
+use crate::event::EventType;
+
+// Only the parts of the WCA Competition Interchange Format used to set up predictions
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Wcif {
+    id: String,
+    name: String,
+    #[serde(default)]
+    persons: Vec<WcifPerson>,
+    #[serde(default)]
+    events: Vec<WcifEvent>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct WcifPerson {
+    name: String,
+    wca_id: Option<String>,
+    registration: Option<WcifRegistration>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct WcifRegistration {
+    #[serde(default)]
+    event_ids: Vec<String>,
+    status: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct WcifEvent {
+    id: String,
+    #[serde(default)]
+    rounds: Vec<WcifRound>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct WcifRound {
+    id: String,
+    format: String,
+    time_limit: Option<TimeLimit>,
+    cutoff: Option<Cutoff>,
+    advancement_condition: Option<AdvancementCondition>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct TimeLimit {
+    pub centiseconds: i32,
+    // Round ids the limit is shared across, empty for a per-attempt limit
+    #[serde(default)]
+    pub cumulative_round_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub struct Cutoff {
+    pub number_of_attempts: usize,
+    // Competitors must beat this result within the first attempts to continue
+    pub attempt_result: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(
+    tag = "type",
+    rename_all(serialize = "snake_case", deserialize = "camelCase")
+)]
+pub enum AdvancementCondition {
+    // The top `level` competitors advance
+    Ranking { level: u32 },
+    // The top `level` percent of competitors advance
+    Percent { level: u32 },
+    // Competitors with a result better than `level` advance
+    AttemptResult { level: i32 },
+}
+
+#[derive(Serialize, Debug)]
+pub struct WcifCompetition {
+    pub id: String,
+    pub name: String,
+    pub events: Vec<WcifEventSetup>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WcifEventSetup {
+    pub event_id: String,
+    // False for events the simulator can't model, e.g. multi-blind
+    pub supported: bool,
+    // WCA ids of accepted registrants, used to load their results
+    pub competitors: Vec<String>,
+    // Registrants without a WCA id (newcomers) have no results to load
+    pub newcomers: Vec<String>,
+    pub rounds: Vec<RoundSetup>,
+}
+
+// Round details are informational only. The simulator always uses the event's default format,
+// with no cutoff or time limit, so these are returned for display and for choosing how many
+// competitors to simulate in a round.
+#[derive(Serialize, Debug)]
+pub struct RoundSetup {
+    pub id: String,
+    pub format: String,
+    pub num_attempts: usize,
+    pub cutoff: Option<Cutoff>,
+    pub time_limit: Option<TimeLimit>,
+    pub advancement: Option<AdvancementCondition>,
+    // Number of competitors advancing, when it's known from the number of competitors
+    pub advancing: Option<u32>,
+}
+
+pub fn parse_wcif(contents: &str) -> Result<WcifCompetition, &'static str> {
+    let wcif: Wcif = serde_json::from_str(contents).map_err(|_| "Failed to parse WCIF")?;
+
+    let accepted: Vec<&WcifPerson> = wcif
+        .persons
+        .iter()
+        .filter(|person| {
+            person
+                .registration
+                .as_ref()
+                .is_some_and(|registration| registration.status == "accepted")
+        })
+        .collect();
+
+    let events = wcif
+        .events
+        .iter()
+        .map(|event| {
+            let registrants: Vec<&WcifPerson> = accepted
+                .iter()
+                .copied()
+                .filter(|person| {
+                    person
+                        .registration
+                        .as_ref()
+                        .is_some_and(|registration| registration.event_ids.contains(&event.id))
+                })
+                .collect();
+
+            let (competitors, newcomers): (Vec<&WcifPerson>, Vec<&WcifPerson>) = registrants
+                .iter()
+                .partition(|person| person.wca_id.is_some());
+
+            WcifEventSetup {
+                event_id: event.id.clone(),
+                supported: EventType::from_event_id(&event.id).is_some(),
+                competitors: competitors
+                    .iter()
+                    .filter_map(|person| person.wca_id.clone())
+                    .collect(),
+                newcomers: newcomers.iter().map(|person| person.name.clone()).collect(),
+                rounds: parse_rounds(&event.rounds, registrants.len() as u32),
+            }
+        })
+        .collect();
+
+    Ok(WcifCompetition {
+        id: wcif.id,
+        name: wcif.name,
+        events,
+    })
+}
+
+// Each round's advancement is relative to the number of competitors in that round, so the
+// number of competitors is carried forward from the previous round's advancement
+fn parse_rounds(rounds: &[WcifRound], num_registrants: u32) -> Vec<RoundSetup> {
+    let mut num_competitors = Some(num_registrants);
+
+    rounds
+        .iter()
+        .map(|round| {
+            let advancing = match (&round.advancement_condition, num_competitors) {
+                (Some(AdvancementCondition::Ranking { level }), Some(n)) => Some((*level).min(n)),
+                (Some(AdvancementCondition::Percent { level }), Some(n)) => Some(n * level / 100),
+                _ => None,
+            };
+
+            num_competitors = advancing;
+
+            RoundSetup {
+                id: round.id.clone(),
+                format: round.format.clone(),
+                num_attempts: format_attempts(&round.format),
+                cutoff: round.cutoff.clone(),
+                time_limit: round.time_limit.clone(),
+                advancement: round.advancement_condition.clone(),
+                advancing,
+            }
+        })
+        .collect()
+}
+
+// WCIF round formats: "1", "2" and "3" are best of n, "a" is average of 5 and "m" is mean of 3
+fn format_attempts(format: &str) -> usize {
+    match format {
+        "1" => 1,
+        "2" => 2,
+        "3" | "m" => 3,
+        "a" => 5,
+        _ => 0,
+    }
+}